@@ -29,17 +29,22 @@ export default defineConfig({
         { text: "Installation", link: "/getting-started/installation" },
         { text: "Dependencies", link: "/getting-started/dependencies" },
         { text: "Configuration", link: "/getting-started/configuration" },
+        { text: "Operations", link: "/getting-started/operations" },
       ],
     },
     {
       text: "API Reference",
       link: "/api",
       items: [
+        { text: "Authentication", link: "/api/authentication" },
         { text: "Common Types", link: "/api/common-types" },
         { text: "JSON-RPC", link: "/api/json-rpc" },
         { text: "Transactions", link: "/api/transactions" },
         { text: "Groups", link: "/api/groups" },
+        { text: "Webhooks", link: "/api/webhooks" },
+        { text: "Events", link: "/api/events" },
         { text: "Health", link: "/api/health" },
+        { text: "Metrics", link: "/api/metrics" },
       ],
     },
     { text: "Concepts", link: "/concepts" },
@@ -0,0 +1,37 @@
+CREATE TABLE IF NOT EXISTS webhooks (
+    id UUID PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    chain_id NUMERIC(20, 0) NOT NULL CONSTRAINT chk_webhook_chain_id_range CHECK (chain_id >= 0 AND chain_id <= 18446744073709551615),
+    sender BYTEA,
+    group_id BYTEA,
+    tx_hash BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_webhook_filter CHECK (
+        (tx_hash IS NOT NULL AND sender IS NULL AND group_id IS NULL)
+        OR (tx_hash IS NULL AND sender IS NOT NULL)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS webhooks_chain_sender_idx ON webhooks (chain_id, sender);
+CREATE INDEX IF NOT EXISTS webhooks_chain_tx_hash_idx ON webhooks (chain_id, tx_hash);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id UUID NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    event_id UUID NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ,
+    lease_owner TEXT,
+    lease_until TIMESTAMPTZ,
+    last_error TEXT,
+    last_status_code INTEGER,
+    delivered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_status_next_idx ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS webhook_deliveries_webhook_idx ON webhook_deliveries (webhook_id);
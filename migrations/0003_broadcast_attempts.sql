@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS broadcast_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    tx_id BIGINT NOT NULL REFERENCES txs (id) ON DELETE CASCADE,
+    attempt INTEGER NOT NULL,
+    endpoint TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    error TEXT,
+    latency_ms BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS broadcast_attempts_tx_idx ON broadcast_attempts (tx_id, id);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    key_hash BYTEA NOT NULL,
+    requests_per_minute INTEGER NOT NULL CONSTRAINT chk_api_key_rpm CHECK (requests_per_minute > 0),
+    txs_per_day INTEGER NOT NULL CONSTRAINT chk_api_key_txs_per_day CHECK (txs_per_day >= 0),
+    max_queued_per_sender INTEGER NOT NULL CONSTRAINT chk_api_key_max_queued CHECK (max_queued_per_sender >= 0),
+    chain_ids BIGINT[],
+    senders BYTEA[],
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS api_keys_key_hash_idx ON api_keys (key_hash);
@@ -0,0 +1,5 @@
+ALTER TABLE txs
+    ADD COLUMN IF NOT EXISTS max_fee_per_gas NUMERIC(39, 0) CONSTRAINT chk_max_fee_per_gas_range CHECK (max_fee_per_gas IS NULL OR (max_fee_per_gas >= 0 AND max_fee_per_gas <= 340282366920938463463374607431768211455)),
+    ADD COLUMN IF NOT EXISTS replaced_by BYTEA;
+
+CREATE INDEX IF NOT EXISTS txs_sender_nonce_idx ON txs (chain_id, sender, nonce_key, nonce);
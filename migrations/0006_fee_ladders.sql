@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS fee_ladders (
+    id UUID PRIMARY KEY,
+    chain_id NUMERIC(20, 0) NOT NULL CONSTRAINT chk_fee_ladder_chain_id_range CHECK (chain_id >= 0 AND chain_id <= 18446744073709551615),
+    escalate_after_attempts INTEGER NOT NULL CONSTRAINT chk_fee_ladder_attempts CHECK (escalate_after_attempts > 0),
+    escalate_after_seconds BIGINT NOT NULL CONSTRAINT chk_fee_ladder_seconds CHECK (escalate_after_seconds > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE txs
+    ADD COLUMN IF NOT EXISTS ladder_id UUID REFERENCES fee_ladders (id),
+    ADD COLUMN IF NOT EXISTS ladder_rung INTEGER;
+
+CREATE INDEX IF NOT EXISTS txs_ladder_idx ON txs (ladder_id, ladder_rung) WHERE ladder_id IS NOT NULL;
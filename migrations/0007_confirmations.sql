@@ -0,0 +1,6 @@
+ALTER TABLE txs
+    ADD COLUMN IF NOT EXISTS receipt_block_number NUMERIC(20, 0) CONSTRAINT chk_receipt_block_number_range CHECK (receipt_block_number IS NULL OR (receipt_block_number >= 0 AND receipt_block_number <= 18446744073709551615)),
+    ADD COLUMN IF NOT EXISTS receipt_block_hash BYTEA;
+
+-- Receipts recorded before block tracking have nothing to check against the canonical chain.
+UPDATE txs SET status = 'confirmed' WHERE status = 'executed';
@@ -0,0 +1,12 @@
+ALTER TABLE txs
+    ADD COLUMN IF NOT EXISTS receipt_success BOOLEAN,
+    ADD COLUMN IF NOT EXISTS receipt_gas_used NUMERIC(20, 0) CONSTRAINT chk_receipt_gas_used_range CHECK (receipt_gas_used IS NULL OR (receipt_gas_used >= 0 AND receipt_gas_used <= 18446744073709551615)),
+    ADD COLUMN IF NOT EXISTS receipt_effective_gas_price NUMERIC(39, 0) CONSTRAINT chk_receipt_effective_gas_price_range CHECK (receipt_effective_gas_price IS NULL OR (receipt_effective_gas_price >= 0 AND receipt_effective_gas_price <= 340282366920938463463374607431768211455)),
+    ADD COLUMN IF NOT EXISTS receipt_fee_token BYTEA;
+
+UPDATE txs
+SET receipt_success = (receipt ->> 'status') = '0x1'
+WHERE receipt IS NOT NULL
+  AND receipt_success IS NULL;
+
+CREATE INDEX IF NOT EXISTS txs_receipt_success_idx ON txs (chain_id, receipt_success) WHERE receipt_success IS NOT NULL;
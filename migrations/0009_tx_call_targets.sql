@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS tx_call_targets (
+    tx_id BIGINT NOT NULL REFERENCES txs (id) ON DELETE CASCADE,
+    target BYTEA NOT NULL,
+    PRIMARY KEY (tx_id, target)
+);
+
+CREATE INDEX IF NOT EXISTS tx_call_targets_target_idx ON tx_call_targets (target, tx_id);
+CREATE INDEX IF NOT EXISTS txs_fee_payer_idx ON txs (chain_id, fee_payer) WHERE fee_payer IS NOT NULL;
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS txs_archive (
+    id BIGINT PRIMARY KEY,
+    chain_id NUMERIC(20, 0) NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    sender BYTEA NOT NULL,
+    status TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    record JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS txs_archive_tx_hash_idx ON txs_archive (chain_id, tx_hash);
+CREATE INDEX IF NOT EXISTS txs_retention_idx ON txs (status, updated_at);
@@ -0,0 +1,13 @@
+CREATE OR REPLACE FUNCTION watchtower_notify_schedulable() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('watchtower_txs', NEW.chain_id::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS txs_notify_schedulable ON txs;
+CREATE TRIGGER txs_notify_schedulable
+    AFTER INSERT OR UPDATE OF status, next_action_at ON txs
+    FOR EACH ROW
+    WHEN (NEW.status IN ('queued', 'retry_scheduled'))
+    EXECUTE FUNCTION watchtower_notify_schedulable();
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS auth_nonces (
+    sender BYTEA NOT NULL,
+    nonce BYTEA NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (sender, nonce)
+);
@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS txs_notify_schedulable ON txs;
+CREATE TRIGGER txs_notify_schedulable
+    AFTER INSERT OR UPDATE OF status, next_action_at ON txs
+    FOR EACH ROW
+    WHEN (NEW.status IN ('queued', 'retry_scheduled', 'broadcasting'))
+    EXECUTE FUNCTION watchtower_notify_schedulable();
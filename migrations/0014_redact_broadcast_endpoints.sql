@@ -0,0 +1,3 @@
+-- Attempts recorded before endpoints were redacted stored the full RPC URL, keys included.
+UPDATE broadcast_attempts
+SET endpoint = regexp_replace(endpoint, '^([A-Za-z][A-Za-z0-9+.-]*://)([^@/?#]*@)?([^/?#]*).*$', '\1\3');
@@ -1,13 +1,20 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+use std::ops::RangeInclusive;
 
-use alloy::network::TransactionBuilder;
 use alloy::primitives::{Bytes, keccak256};
-use alloy::providers::Provider;
 use axum::{
-    Json, Router,
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
+    Extension, Json, Router,
+    extract::{
+        Path, Request, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::{self, Next},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{get, post},
 };
 use axum_extra::extract::Query;
@@ -15,20 +22,31 @@ use chrono::{DateTime, TimeZone, Utc};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx_pg_uint::{OptionPgUint, PgU64};
-use tempo_alloy::primitives::transaction::{Call, PrimitiveSignature};
-use tracing::{error, info};
-
+use sqlx::{Postgres, Transaction};
+use sqlx_pg_uint::{OptionPgUint, PgU64, PgU128};
+use tempo_alloy::primitives::transaction::Call;
+use tokio::sync::broadcast;
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::api_keys::{self, Quota};
+use crate::auth::{self, AuthError, SignedAction};
 use crate::db;
-use crate::models::{NewTx, TxRecord, TxStatus};
+use crate::events::{self, EventFilter};
+use crate::models::{ApiKeyRecord, NewTx, NewWebhook, TxEvent, TxRecord, TxStatus, WebhookRecord};
 use crate::nonce_key::{decode_group_nonce_key, is_group_nonce_key};
+use crate::retention;
+use crate::rpc::fetch_current_nonce;
 use crate::scheduler;
+use crate::shutdown::Shutdown;
 use crate::state::AppState;
+use crate::telemetry;
 use crate::tx::parse_raw_tx;
+use crate::webhooks;
 
 pub fn router(state: AppState) -> Router {
     Router::new()
-        .route("/health", get(health))
         .route("/rpc", post(rpc_handler))
         .route(
             "/v1/transactions",
@@ -38,21 +56,42 @@ pub fn router(state: AppState) -> Router {
             "/v1/transactions/{tx_hash}",
             get(get_transaction).delete(cancel_transaction),
         )
+        .route(
+            "/v1/transactions/{tx_hash}/attempts",
+            get(list_broadcast_attempts),
+        )
         .route("/v1/groups", get(list_groups))
         .route("/v1/senders/{sender}/groups/{group_id}", get(get_group))
         .route(
             "/v1/senders/{sender}/groups/{group_id}/cancel",
             post(cancel_group),
         )
+        .route(
+            "/v1/senders/{sender}/groups/{group_id}/pause",
+            post(pause_group),
+        )
+        .route(
+            "/v1/senders/{sender}/groups/{group_id}/resume",
+            post(resume_group),
+        )
+        .route("/v1/webhooks", post(create_webhook))
+        .route("/v1/webhooks/{id}", get(get_webhook).delete(delete_webhook))
+        .route("/v1/events", get(stream_events))
+        .route("/v1/events/ws", get(stream_events_ws))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .route("/health", get(health))
+        .route("/metrics", get(metrics))
         .with_state(state)
 }
 
-const GROUP_SIGNATURE_HEADER: &str = "authorization";
+// Pending transactions clear as they execute, so there is no exact time to suggest.
+const QUEUED_LIMIT_RETRY_AFTER_SECS: u64 = 30;
 
 #[derive(Debug)]
 struct ApiError {
     status: StatusCode,
     message: String,
+    retry_after: Option<u64>,
 }
 
 impl ApiError {
@@ -60,6 +99,7 @@ impl ApiError {
         Self {
             status: StatusCode::BAD_REQUEST,
             message: message.into(),
+            retry_after: None,
         }
     }
 
@@ -67,6 +107,7 @@ impl ApiError {
         Self {
             status: StatusCode::NOT_FOUND,
             message: message.into(),
+            retry_after: None,
         }
     }
 
@@ -74,6 +115,7 @@ impl ApiError {
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
             message: message.into(),
+            retry_after: None,
         }
     }
 
@@ -81,6 +123,33 @@ impl ApiError {
         Self {
             status: StatusCode::UNAUTHORIZED,
             message: message.into(),
+            retry_after: None,
+        }
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            message: message.into(),
+            retry_after: None,
+        }
+    }
+
+    fn too_many_requests(message: impl Into<String>, retry_after_secs: u64) -> Self {
+        Self {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            message: message.into(),
+            retry_after: Some(retry_after_secs),
+        }
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(err: AuthError) -> Self {
+        match err {
+            AuthError::BadRequest(message) => Self::bad_request(message),
+            AuthError::Unauthorized(message) => Self::unauthorized(message),
+            AuthError::Internal(err) => Self::internal(err.to_string()),
         }
     }
 }
@@ -88,7 +157,13 @@ impl ApiError {
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let body = Json(serde_json::json!({ "error": self.message }));
-        (self.status, body).into_response()
+        let mut response = (self.status, body).into_response();
+        if let Some(retry_after) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
     }
 }
 
@@ -104,10 +179,12 @@ struct HealthResponse {
     uptime_seconds: i64,
     chains: Vec<u64>,
     rpc_endpoints: usize,
+    rpc_health: Vec<HealthRpcChain>,
     scheduler: HealthSchedulerInfo,
     watcher: HealthWatcherInfo,
     broadcaster: HealthBroadcasterInfo,
     api: HealthApiInfo,
+    retention: HealthRetentionInfo,
     dependencies: HealthDependencies,
 }
 
@@ -120,9 +197,29 @@ struct HealthBuildInfo {
     build_timestamp: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthRpcChain {
+    chain_id: u64,
+    endpoints: Vec<HealthRpcEndpoint>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthRpcEndpoint {
+    url: String,
+    circuit: String,
+    score: f64,
+    success_rate: f64,
+    latency_ms: f64,
+    consecutive_failures: u32,
+    requests: u64,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct HealthSchedulerInfo {
+    mode: &'static str,
     poll_interval_ms: u64,
     lease_ttl_seconds: i64,
     max_concurrency: usize,
@@ -152,11 +249,23 @@ struct HealthApiInfo {
     max_body_bytes: usize,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthRetentionInfo {
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_run_at: Option<i64>,
+    stripped: u64,
+    archived: u64,
+    exported: u64,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct HealthDependencies {
     database: HealthDependency,
-    redis: HealthDependency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redis: Option<HealthDependency>,
 }
 
 #[derive(Debug, Serialize)]
@@ -169,13 +278,37 @@ struct HealthDependency {
 #[serde(rename_all = "camelCase")]
 struct SubmitRequest {
     chain_id: u64,
+    #[serde(default)]
     transactions: Vec<String>,
+    #[serde(default)]
+    escalation: Vec<EscalationRequest>,
+    /// Supersede pending transactions with the same sender, nonce key and nonce.
+    #[serde(default)]
+    replace: bool,
+}
+
+/// Pre-signed variants of one nonce at increasing `max_fee_per_gas`, broadcast cheapest first.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EscalationRequest {
+    rungs: Vec<String>,
+    escalate_after_attempts: Option<i32>,
+    escalate_after_seconds: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct SubmitResponse {
     results: Vec<SubmitResult>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ladders: Vec<LadderResult>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LadderResult {
+    ladder_id: String,
+    results: Vec<SubmitResult>,
 }
 
 #[derive(Debug, Serialize)]
@@ -204,6 +337,18 @@ struct SubmitResult {
     error: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastAttemptInfo {
+    attempt: i32,
+    endpoint: String,
+    outcome: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    latency_ms: i64,
+    created_at: i64,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct TxInfo {
@@ -234,6 +379,22 @@ struct TxInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     receipt: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    reverted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effective_gas_price: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fee_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replaced_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ladder_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ladder_rung: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     gas: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     gas_price: Option<u128>,
@@ -252,6 +413,8 @@ struct TxInfo {
 struct TxListQuery {
     chain_id: Option<u64>,
     sender: Option<String>,
+    fee_payer: Option<String>,
+    to: Option<String>,
     group_id: Option<String>,
     ungrouped: Option<bool>,
     #[serde(
@@ -259,15 +422,50 @@ struct TxListQuery {
         deserialize_with = "crate::serde_helpers::deserialize_string_or_vec"
     )]
     status: Vec<String>,
+    reverted: Option<bool>,
+    created_after: Option<i64>,
+    created_before: Option<i64>,
+    eligible_after: Option<i64>,
+    eligible_before: Option<i64>,
+    updated_after: Option<i64>,
+    updated_before: Option<i64>,
+    sort: Option<String>,
+    order: Option<String>,
+    cursor: Option<String>,
     limit: Option<i64>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TxListResponse {
+    transactions: Vec<TxInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ChainQuery {
     chain_id: Option<u64>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GroupQuery {
+    chain_id: Option<u64>,
+    from_nonce: Option<u64>,
+    to_nonce: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventStreamQuery {
+    chain_id: Option<u64>,
+    sender: Option<String>,
+    group_id: Option<String>,
+    tx_hash: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct GroupListQuery {
@@ -275,6 +473,19 @@ struct GroupListQuery {
     chain_id: Option<u64>,
     limit: Option<i64>,
     active: Option<bool>,
+    created_after: Option<i64>,
+    created_before: Option<i64>,
+    sort: Option<String>,
+    order: Option<String>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GroupListResponse {
+    groups: Vec<GroupSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -350,6 +561,47 @@ struct CancelResponse {
     tx_hashes: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PauseResponse {
+    paused: usize,
+    tx_hashes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumeResponse {
+    resumed: usize,
+    tx_hashes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateWebhookRequest {
+    url: String,
+    chain_id: u64,
+    sender: Option<String>,
+    group_id: Option<String>,
+    tx_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookInfo {
+    id: Uuid,
+    url: String,
+    chain_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+    created_at: i64,
+}
+
 #[derive(Debug)]
 struct RpcRequest {
     id: Value,
@@ -364,22 +616,48 @@ struct RpcError {
 }
 
 async fn health(State(state): State<AppState>) -> impl IntoResponse {
+    let config = state.config.current();
     let now = Utc::now();
     let started_at = state.started_at;
     let uptime_seconds = now.signed_duration_since(started_at).num_seconds().max(0);
 
-    let mut chains: Vec<u64> = state.config.rpc.chains.keys().copied().collect();
+    let mut chains: Vec<u64> = config.rpc.chains.keys().copied().collect();
     chains.sort_unstable();
-    let rpc_endpoints = state.config.rpc.chains.values().map(Vec::len).sum();
+    let rpc_endpoints = config.rpc.chains.values().map(Vec::len).sum();
+
+    let rpc_health = state
+        .rpcs
+        .chain_ids()
+        .into_iter()
+        .filter_map(|chain_id| state.rpcs.chain(chain_id))
+        .map(|chain| HealthRpcChain {
+            chain_id: chain.chain_id,
+            endpoints: chain
+                .health_snapshot()
+                .into_iter()
+                .map(|endpoint| HealthRpcEndpoint {
+                    url: endpoint.url,
+                    circuit: endpoint.state.as_str().to_string(),
+                    score: endpoint.score,
+                    success_rate: endpoint.success_rate,
+                    latency_ms: endpoint.latency_ms,
+                    consecutive_failures: endpoint.consecutive_failures,
+                    requests: endpoint.requests,
+                })
+                .collect(),
+        })
+        .collect();
 
     let db_ok = sqlx::query("SELECT 1").execute(&state.db).await.is_ok();
 
-    let redis_ok = {
-        let mut redis_conn = state.redis.clone();
-        redis_conn.ping::<String>().await.is_ok()
+    let redis_ok = match state.redis.clone() {
+        Some(mut redis_conn) => Some(redis_conn.ping::<String>().await.is_ok()),
+        None => None,
     };
+    let healthy = db_ok && redis_ok.unwrap_or(true);
 
-    let status = if db_ok && redis_ok { "ok" } else { "degraded" };
+    let status = if healthy { "ok" } else { "degraded" };
+    let retention_stats = retention::stats();
 
     let response = HealthResponse {
         status: status.to_string(),
@@ -394,33 +672,46 @@ async fn health(State(state): State<AppState>) -> impl IntoResponse {
         uptime_seconds,
         chains,
         rpc_endpoints,
+        rpc_health,
         scheduler: HealthSchedulerInfo {
-            poll_interval_ms: state.config.scheduler.poll_interval_ms,
-            lease_ttl_seconds: state.config.scheduler.lease_ttl_seconds,
-            max_concurrency: state.config.scheduler.max_concurrency,
-            retry_min_ms: state.config.scheduler.retry_min_ms,
-            retry_max_ms: state.config.scheduler.retry_max_ms,
-            expiry_soon_window_seconds: state.config.scheduler.expiry_soon_window_seconds,
-            expiry_soon_retry_max_ms: state.config.scheduler.expiry_soon_retry_max_ms,
+            mode: if state.redis.is_some() {
+                "redis"
+            } else {
+                "postgres"
+            },
+            poll_interval_ms: config.scheduler.poll_interval_ms,
+            lease_ttl_seconds: config.scheduler.lease_ttl_seconds,
+            max_concurrency: config.scheduler.max_concurrency,
+            retry_min_ms: config.scheduler.retry_min_ms,
+            retry_max_ms: config.scheduler.retry_max_ms,
+            expiry_soon_window_seconds: config.scheduler.expiry_soon_window_seconds,
+            expiry_soon_retry_max_ms: config.scheduler.expiry_soon_retry_max_ms,
         },
         watcher: HealthWatcherInfo {
-            poll_interval_ms: state.config.watcher.poll_interval_ms,
-            use_websocket: state.config.watcher.use_websocket,
+            poll_interval_ms: config.watcher.poll_interval_ms,
+            use_websocket: config.watcher.use_websocket,
         },
         broadcaster: HealthBroadcasterInfo {
-            fanout: state.config.broadcaster.fanout,
-            timeout_ms: state.config.broadcaster.timeout_ms,
+            fanout: config.broadcaster.fanout,
+            timeout_ms: config.broadcaster.timeout_ms,
         },
         api: HealthApiInfo {
-            max_body_bytes: state.config.api.max_body_bytes,
+            max_body_bytes: config.api.max_body_bytes,
+        },
+        retention: HealthRetentionInfo {
+            enabled: config.retention.enabled,
+            last_run_at: retention_stats.last_run_at.map(|at| at.timestamp()),
+            stripped: retention_stats.stripped,
+            archived: retention_stats.archived,
+            exported: retention_stats.exported,
         },
         dependencies: HealthDependencies {
             database: HealthDependency { ok: db_ok },
-            redis: HealthDependency { ok: redis_ok },
+            redis: redis_ok.map(|ok| HealthDependency { ok }),
         },
     };
 
-    let http_status = if db_ok && redis_ok {
+    let http_status = if healthy {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
@@ -429,13 +720,26 @@ async fn health(State(state): State<AppState>) -> impl IntoResponse {
     (http_status, Json(response))
 }
 
+async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        telemetry::render(&state).await,
+    )
+}
+
 async fn submit_transactions(
     State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyRecord>>,
     Json(payload): Json<SubmitRequest>,
 ) -> Result<Json<SubmitResponse>, ApiError> {
     let SubmitRequest {
         chain_id,
         transactions,
+        escalation,
+        replace,
     } = payload;
     if state.rpcs.chain(chain_id).is_none() {
         return Err(ApiError::bad_request(format!(
@@ -456,26 +760,77 @@ async fn submit_transactions(
         prepared.push(new_tx);
     }
 
-    let (records, already_known_flags) = store_transactions(&state, prepared).await?;
-
-    let mut results = Vec::with_capacity(records.len());
-    for (record, already_known) in records.into_iter().zip(already_known_flags) {
-        results.push(SubmitResult {
-            ok: true,
-            tx_hash: Some(bytes_to_hex(&record.tx_hash)),
-            sender: Some(bytes_to_hex(&record.sender)),
-            nonce_key: Some(u256_bytes_to_hex(&record.nonce_key)),
-            nonce: Some(record.nonce.to_uint()),
-            group_id: record.group_id.as_ref().map(|value| bytes_to_hex(value)),
-            eligible_at: Some(record.eligible_at.timestamp()),
-            expires_at: record.expires_at.map(|ts| ts.timestamp()),
-            status: Some(record.status.clone()),
-            already_known: Some(already_known),
-            error: None,
-        });
+    let config = state.config.current();
+    let mut ladders = Vec::with_capacity(escalation.len());
+    for (index, ladder) in escalation.into_iter().enumerate() {
+        let mut rungs = Vec::with_capacity(ladder.rungs.len());
+        for (rung, raw_tx) in ladder.rungs.iter().enumerate() {
+            let new_tx = prepare_new_tx(chain_id, raw_tx).map_err(|err| {
+                ApiError::bad_request(format!(
+                    "escalation {index} rung {rung} invalid: {}",
+                    err.message
+                ))
+            })?;
+            rungs.push(new_tx);
+        }
+        validate_fee_ladder(&rungs, config.api.replacement_bump_percent).map_err(|err| {
+            ApiError::bad_request(format!("escalation {index} invalid: {}", err.message))
+        })?;
+
+        let escalate_after_attempts = ladder
+            .escalate_after_attempts
+            .unwrap_or(config.scheduler.escalate_after_attempts);
+        let escalate_after_seconds = ladder
+            .escalate_after_seconds
+            .unwrap_or(config.scheduler.escalate_after_seconds);
+        if escalate_after_attempts <= 0 || escalate_after_seconds <= 0 {
+            return Err(ApiError::bad_request(format!(
+                "escalation {index} invalid: escalateAfterAttempts and escalateAfterSeconds must be positive"
+            )));
+        }
+        ladders.push((rungs, escalate_after_attempts, escalate_after_seconds));
     }
 
-    Ok(Json(SubmitResponse { results }))
+    let api_key = api_key.as_ref().map(|Extension(api_key)| api_key);
+    let (records, already_known_flags, ladders) =
+        store_submission(&state, api_key, prepared, ladders, replace).await?;
+
+    let results = records
+        .iter()
+        .zip(already_known_flags)
+        .map(|(record, already_known)| submit_result_from(record, already_known))
+        .collect();
+    let ladder_results = ladders
+        .into_iter()
+        .map(|(ladder_id, records, already_known)| LadderResult {
+            ladder_id: ladder_id.to_string(),
+            results: records
+                .iter()
+                .map(|record| submit_result_from(record, already_known))
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(SubmitResponse {
+        results,
+        ladders: ladder_results,
+    }))
+}
+
+fn submit_result_from(record: &TxRecord, already_known: bool) -> SubmitResult {
+    SubmitResult {
+        ok: true,
+        tx_hash: Some(bytes_to_hex(&record.tx_hash)),
+        sender: Some(bytes_to_hex(&record.sender)),
+        nonce_key: Some(u256_bytes_to_hex(&record.nonce_key)),
+        nonce: Some(record.nonce.to_uint()),
+        group_id: record.group_id.as_ref().map(|value| bytes_to_hex(value)),
+        eligible_at: Some(record.eligible_at.timestamp()),
+        expires_at: record.expires_at.map(|ts| ts.timestamp()),
+        status: Some(record.status.clone()),
+        already_known: Some(already_known),
+        error: None,
+    }
 }
 
 fn prepare_new_tx(chain_id: u64, raw_tx: &str) -> Result<NewTx, ApiError> {
@@ -543,10 +898,22 @@ fn prepare_new_tx_from_parsed(parsed: crate::tx::ParsedTx) -> Result<NewTx, ApiE
         status: TxStatus::Queued.as_str().to_string(),
         group_id,
         next_action_at: eligible_at,
+        max_fee_per_gas: PgU128::from(parsed.max_fee_per_gas),
+        ladder_id: None,
+        ladder_rung: None,
+        call_targets: parsed
+            .call_targets()
+            .iter()
+            .map(|target| target.as_slice().to_vec())
+            .collect(),
     })
 }
 
-async fn rpc_handler(State(state): State<AppState>, Json(payload): Json<Value>) -> Json<Value> {
+async fn rpc_handler(
+    State(state): State<AppState>,
+    api_key: Option<Extension<ApiKeyRecord>>,
+    Json(payload): Json<Value>,
+) -> Json<Value> {
     let request = match parse_rpc_request(&payload) {
         Ok(request) => request,
         Err(err) => return rpc_error_response(Value::Null, err),
@@ -615,16 +982,21 @@ async fn rpc_handler(State(state): State<AppState>, Json(payload): Json<Value>)
         }
     };
 
-    let result = store_transactions(&state, vec![new_tx]).await;
+    let api_key = api_key.as_ref().map(|Extension(api_key)| api_key);
+    let result = store_submission(&state, api_key, vec![new_tx], Vec::new(), false).await;
     let record = match result {
-        Ok((mut records, _)) => records
+        Ok((mut records, _, _)) => records
             .pop()
-            .expect("store_transactions returns at least one record"),
+            .expect("store_submission returns a record per transaction"),
         Err(err) => {
+            let code = match err.status {
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => -32005,
+                _ => -32603,
+            };
             return rpc_error_response(
                 request.id,
                 RpcError {
-                    code: -32603,
+                    code,
                     message: err.message,
                 },
             );
@@ -714,8 +1086,42 @@ async fn get_transaction(
     Ok(Json(tx_info_from(&record)?))
 }
 
+async fn list_broadcast_attempts(
+    State(state): State<AppState>,
+    Path(tx_hash): Path<String>,
+    Query(query): Query<ChainQuery>,
+) -> Result<Json<Vec<BroadcastAttemptInfo>>, ApiError> {
+    let tx_hash = parse_fixed_hex(&tx_hash, 32)?;
+    let record = db::get_tx_by_hash(&state.db, query.chain_id, &tx_hash)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?
+        .ok_or_else(|| ApiError::not_found("transaction not found"))?;
+
+    let attempts = db::list_broadcast_attempts(&state.db, record.id)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+
+    Ok(Json(
+        attempts
+            .into_iter()
+            .map(|attempt| BroadcastAttemptInfo {
+                attempt: attempt.attempt,
+                endpoint: attempt.endpoint,
+                outcome: attempt.outcome,
+                error: attempt.error,
+                latency_ms: attempt.latency_ms,
+                created_at: attempt.created_at.timestamp(),
+            })
+            .collect(),
+    ))
+}
+
+/// With an `Authorization` signature from the sender the transaction is canceled locally,
+/// whatever its nonce. Without one it is only marked `stale_by_nonce`, once its nonce has been
+/// used on-chain.
 async fn cancel_transaction(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(tx_hash): Path<String>,
     Query(query): Query<ChainQuery>,
 ) -> Result<Json<TxInfo>, ApiError> {
@@ -725,10 +1131,27 @@ async fn cancel_transaction(
         .map_err(|err| ApiError::internal(err.to_string()))?
         .ok_or_else(|| ApiError::not_found("transaction not found"))?;
 
+    if headers.contains_key(auth::AUTHORIZATION_HEADER) {
+        auth::authorize(
+            &state,
+            &headers,
+            &record.sender,
+            Some(record.chain_id.to_uint()),
+            SignedAction::CancelTransaction(&tx_hash_bytes),
+        )
+        .await?;
+        return cancel_transaction_locally(&state, &record).await;
+    }
+
     let status = TxStatus::try_from(record.status.as_str())
         .map_err(|_| ApiError::internal("invalid transaction status"))?;
     match status {
-        TxStatus::Executed | TxStatus::Expired | TxStatus::Invalid | TxStatus::CanceledLocally => {
+        TxStatus::Executed
+        | TxStatus::Confirmed
+        | TxStatus::Expired
+        | TxStatus::Invalid
+        | TxStatus::CanceledLocally
+        | TxStatus::Replaced => {
             return Err(ApiError::bad_request("transaction already terminal"));
         }
         _ => {}
@@ -741,7 +1164,7 @@ async fn cancel_transaction(
         .ok_or_else(|| ApiError::internal("missing rpc chain"))?;
     let sender_addr =
         parse_address(&record.sender).map_err(|err| ApiError::internal(err.to_string()))?;
-    let current_nonce = fetch_current_nonce(chain, sender_addr, &record.nonce_key)
+    let current_nonce = fetch_current_nonce(&chain, sender_addr, &record.nonce_key)
         .await
         .map_err(|err| ApiError::internal(err.to_string()))?
         .ok_or_else(|| ApiError::internal("missing current nonce"))?;
@@ -752,22 +1175,14 @@ async fn cancel_transaction(
     }
 
     if !matches!(status, TxStatus::StaleByNonce) {
-        db::mark_stale_by_nonce(&state.db, record.id)
+        if let Some(updated) = db::mark_stale_by_nonce(&state.db, record.id)
             .await
-            .map_err(|err| ApiError::internal(err.to_string()))?;
+            .map_err(|err| ApiError::internal(err.to_string()))?
+        {
+            events::publish(&state, &updated).await;
+        }
 
-        let tx_hash_hex = bytes_to_hex(&record.tx_hash);
-        let mut redis = state.redis.clone();
-        let ready_key = ready_key(chain_id);
-        let retry_key = retry_key(chain_id);
-        let _: () = redis
-            .zrem::<_, _, ()>(ready_key, &tx_hash_hex)
-            .await
-            .unwrap_or(());
-        let _: () = redis
-            .zrem::<_, _, ()>(retry_key, &tx_hash_hex)
-            .await
-            .unwrap_or(());
+        let _ = scheduler::unschedule_records(&state, std::slice::from_ref(&record)).await;
     }
 
     let record = db::get_tx_by_hash(&state.db, Some(chain_id), &tx_hash_bytes)
@@ -781,11 +1196,19 @@ async fn cancel_transaction(
 async fn list_transactions(
     State(state): State<AppState>,
     Query(query): Query<TxListQuery>,
-) -> Result<Json<Vec<TxInfo>>, ApiError> {
+) -> Result<Json<TxListResponse>, ApiError> {
     let sender = match query.sender {
         Some(value) => Some(parse_fixed_hex(&value, 20)?),
         None => None,
     };
+    let fee_payer = match query.fee_payer {
+        Some(value) => Some(parse_fixed_hex(&value, 20)?),
+        None => None,
+    };
+    let to = match query.to {
+        Some(value) => Some(parse_fixed_hex(&value, 20)?),
+        None => None,
+    };
     let group_id = match query.group_id {
         Some(value) => Some(parse_fixed_hex(&value, 16)?),
         None => None,
@@ -806,42 +1229,95 @@ async fn list_transactions(
         })
         .collect::<Result<Vec<TxStatus>, ApiError>>()?;
 
+    let (sort, order, after) = parse_page(
+        query.sort.as_deref(),
+        query.order.as_deref(),
+        query.cursor.as_deref(),
+    )?;
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
     let filters = db::TxFilters {
         chain_id: query.chain_id,
         sender,
+        fee_payer,
+        to,
         group_id,
         ungrouped,
         statuses,
-        limit: query.limit.unwrap_or(100).min(500),
+        reverted: query.reverted,
+        created_after: parse_time_filter(query.created_after, "createdAfter")?,
+        created_before: parse_time_filter(query.created_before, "createdBefore")?,
+        eligible_after: parse_time_filter(query.eligible_after, "eligibleAfter")?,
+        eligible_before: parse_time_filter(query.eligible_before, "eligibleBefore")?,
+        updated_after: parse_time_filter(query.updated_after, "updatedAfter")?,
+        updated_before: parse_time_filter(query.updated_before, "updatedBefore")?,
+        sort,
+        order,
+        after,
+        limit,
     };
 
-    let records = db::list_txs(&state.db, filters)
+    let mut records = db::list_txs(&state.db, filters)
         .await
         .map_err(|err| ApiError::internal(err.to_string()))?;
-
-    let mut out = Vec::with_capacity(records.len());
+    let next_cursor = next_page(&mut records, limit, |record| db::PageCursor {
+        sort,
+        order,
+        value: sort.value_of(record),
+        id: record.id,
+    });
+
+    let mut transactions = Vec::with_capacity(records.len());
     for record in &records {
-        out.push(tx_info_from(record)?);
+        transactions.push(tx_info_from(record)?);
     }
 
-    Ok(Json(out))
+    Ok(Json(TxListResponse {
+        transactions,
+        next_cursor,
+    }))
 }
 
 async fn list_groups(
     State(state): State<AppState>,
     Query(query): Query<GroupListQuery>,
-) -> Result<Json<Vec<GroupSummary>>, ApiError> {
-    let sender_bytes = match query.sender {
+) -> Result<Json<GroupListResponse>, ApiError> {
+    let sender = match query.sender {
         Some(sender) => Some(parse_fixed_hex(&sender, 20)?),
         None => None,
     };
 
-    let limit = query.limit.unwrap_or(100).min(500);
-    let active_only = query.active.unwrap_or(false);
-    let records =
-        db::list_sender_groups(&state.db, &sender_bytes, query.chain_id, limit, active_only)
-            .await
-            .map_err(|err| ApiError::internal(err.to_string()))?;
+    let (sort, order, after) = parse_page(
+        query.sort.as_deref(),
+        query.order.as_deref(),
+        query.cursor.as_deref(),
+    )?;
+    if sort.group_column().is_none() {
+        return Err(ApiError::bad_request(format!(
+            "groups cannot be sorted by {}",
+            sort.as_str()
+        )));
+    }
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let filters = db::GroupFilters {
+        chain_id: query.chain_id,
+        sender,
+        active_only: query.active.unwrap_or(false),
+        created_after: parse_time_filter(query.created_after, "createdAfter")?,
+        created_before: parse_time_filter(query.created_before, "createdBefore")?,
+        sort,
+        order,
+        after,
+        limit,
+    };
+    let mut records = db::list_sender_groups(&state.db, filters)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    let next_cursor = next_page(&mut records, limit, |record| db::PageCursor {
+        sort,
+        order,
+        value: record.sort_value(sort),
+        id: record.first_id,
+    });
 
     let mut out = Vec::with_capacity(records.len());
     for record in &records {
@@ -859,7 +1335,10 @@ async fn list_groups(
         });
     }
 
-    Ok(Json(out))
+    Ok(Json(GroupListResponse {
+        groups: out,
+        next_cursor,
+    }))
 }
 
 fn extract_next_transaction_calls(raw_tx: Option<&[u8]>) -> Option<Vec<CallSummary>> {
@@ -903,14 +1382,18 @@ fn extract_next_transaction_calls(raw_tx: Option<&[u8]>) -> Option<Vec<CallSumma
 async fn get_group(
     State(state): State<AppState>,
     Path((sender, group_id)): Path<(String, String)>,
-    Query(query): Query<ChainQuery>,
+    Query(query): Query<GroupQuery>,
 ) -> Result<Json<GroupResponse>, ApiError> {
     let sender_bytes = parse_fixed_hex(&sender, 20)?;
     let group_bytes = parse_fixed_hex(&group_id, 16)?;
+    let nonces = parse_nonce_range(query.from_nonce, query.to_nonce)?;
 
     let mut records = db::get_group_txs(&state.db, &sender_bytes, &group_bytes, query.chain_id)
         .await
         .map_err(|err| ApiError::internal(err.to_string()))?;
+    if let Some(nonces) = &nonces {
+        records.retain(|record| nonces.contains(&record.nonce.to_uint()));
+    }
 
     if records.is_empty() {
         return Err(ApiError::not_found("group not found"));
@@ -963,41 +1446,71 @@ async fn get_group(
     }))
 }
 
+async fn cancel_transaction_locally(
+    state: &AppState,
+    record: &TxRecord,
+) -> Result<Json<TxInfo>, ApiError> {
+    let records = db::cancel_tx(&state.db, record.id)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    let canceled = records
+        .iter()
+        .find(|canceled| canceled.id == record.id)
+        .ok_or_else(|| ApiError::bad_request("transaction already terminal"))?;
+
+    let _ = scheduler::unschedule_records(state, &records).await;
+    for record in &records {
+        events::publish(state, record).await;
+    }
+    info!(
+        chain_id = %canceled.chain_id.to_uint(),
+        tx_hash = %bytes_to_hex(&canceled.tx_hash),
+        canceled = records.len(),
+        "transaction canceled locally",
+    );
+
+    Ok(Json(tx_info_from(canceled)?))
+}
+
 async fn cancel_group(
     State(state): State<AppState>,
     headers: HeaderMap,
     Path((sender, group_id)): Path<(String, String)>,
+    Query(query): Query<GroupQuery>,
 ) -> Result<Json<CancelResponse>, ApiError> {
     let sender_bytes = parse_fixed_hex(&sender, 20)?;
     let group_bytes = parse_fixed_hex(&group_id, 16)?;
-    verify_group_signature(&headers, &sender_bytes, &group_bytes)?;
-
-    let records = db::cancel_group(&state.db, &sender_bytes, &group_bytes)
-        .await
-        .map_err(|err| ApiError::internal(err.to_string()))?;
+    let nonces = parse_nonce_range(query.from_nonce, query.to_nonce)?;
+    let action = match &nonces {
+        Some(nonces) => SignedAction::CancelGroupNonces {
+            group_id: &group_bytes,
+            from_nonce: *nonces.start(),
+            to_nonce: *nonces.end(),
+        },
+        None => SignedAction::CancelGroup(&group_bytes),
+    };
+    auth::authorize(&state, &headers, &sender_bytes, query.chain_id, action).await?;
+
+    let records = db::cancel_group(
+        &state.db,
+        &sender_bytes,
+        &group_bytes,
+        query.chain_id,
+        nonces.unwrap_or(0..=u64::MAX),
+    )
+    .await
+    .map_err(|err| ApiError::internal(err.to_string()))?;
 
     if records.is_empty() {
-        return Err(ApiError::not_found("group not found"));
+        return Err(ApiError::not_found("no cancelable transactions in group"));
     }
 
     let mut tx_hashes = Vec::with_capacity(records.len());
-
-    let mut redis = state.redis.clone();
     for record in &records {
-        let tx_hash = bytes_to_hex(&record.tx_hash);
-        tx_hashes.push(tx_hash.clone());
-        let chain_id = record.chain_id.to_uint();
-        let ready_key = ready_key(chain_id);
-        let retry_key = retry_key(chain_id);
-        let _: () = redis
-            .zrem::<_, _, ()>(ready_key, &tx_hash)
-            .await
-            .unwrap_or(());
-        let _: () = redis
-            .zrem::<_, _, ()>(retry_key, &tx_hash)
-            .await
-            .unwrap_or(());
+        events::publish(&state, record).await;
+        tx_hashes.push(bytes_to_hex(&record.tx_hash));
     }
+    let _ = scheduler::unschedule_records(&state, &records).await;
 
     Ok(Json(CancelResponse {
         canceled: records.len(),
@@ -1005,47 +1518,617 @@ async fn cancel_group(
     }))
 }
 
-async fn store_transactions(
-    state: &AppState,
-    prepared: Vec<NewTx>,
-) -> Result<(Vec<TxRecord>, Vec<bool>), ApiError> {
-    type GroupKey = (u64, Vec<u8>, Vec<u8>);
-    type NonceWindow = (u64, Option<u64>);
+async fn pause_group(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((sender, group_id)): Path<(String, String)>,
+    Query(query): Query<ChainQuery>,
+) -> Result<Json<PauseResponse>, ApiError> {
+    let sender_bytes = parse_fixed_hex(&sender, 20)?;
+    let group_bytes = parse_fixed_hex(&group_id, 16)?;
+    auth::authorize(
+        &state,
+        &headers,
+        &sender_bytes,
+        query.chain_id,
+        SignedAction::PauseGroup(&group_bytes),
+    )
+    .await?;
+    let chain_id = query
+        .chain_id
+        .ok_or_else(|| ApiError::bad_request("chainId is required"))?;
 
-    let mut db_tx = state
-        .db
-        .begin()
+    let records = db::pause_group(&state.db, &sender_bytes, &group_bytes, chain_id)
         .await
         .map_err(|err| ApiError::internal(err.to_string()))?;
-
-    let mut group_nonce_keys: BTreeMap<GroupKey, Vec<u8>> = BTreeMap::new();
-    let mut group_windows: BTreeMap<GroupKey, Vec<NonceWindow>> = BTreeMap::new();
-    for new_tx in &prepared {
-        let Some(group_id) = new_tx.group_id.as_ref() else {
-            continue;
-        };
-        let key = (
-            new_tx.chain_id.to_uint(),
-            new_tx.sender.clone(),
-            group_id.clone(),
-        );
-        if let Some(existing) = group_nonce_keys.get(&key) {
-            if existing != &new_tx.nonce_key {
-                return Err(ApiError::bad_request(
-                    "group transactions must share the same nonce_key",
-                ));
-            }
-        } else {
-            group_nonce_keys.insert(key.clone(), new_tx.nonce_key.clone());
-        }
-        group_windows.entry(key).or_default().push((
-            new_tx.nonce.to_uint(),
-            new_tx.valid_before.as_ref().map(|value| value.to_uint()),
-        ));
+    if records.is_empty() {
+        return Err(ApiError::not_found("no pausable transactions in group"));
+    }
+
+    let _ = scheduler::unschedule_records(&state, &records).await;
+    let mut tx_hashes = Vec::with_capacity(records.len());
+    for record in &records {
+        events::publish(&state, record).await;
+        tx_hashes.push(bytes_to_hex(&record.tx_hash));
+    }
+    info!(%chain_id, %group_id, paused = records.len(), "group paused");
+
+    Ok(Json(PauseResponse {
+        paused: records.len(),
+        tx_hashes,
+    }))
+}
+
+async fn resume_group(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((sender, group_id)): Path<(String, String)>,
+    Query(query): Query<ChainQuery>,
+) -> Result<Json<ResumeResponse>, ApiError> {
+    let sender_bytes = parse_fixed_hex(&sender, 20)?;
+    let group_bytes = parse_fixed_hex(&group_id, 16)?;
+    auth::authorize(
+        &state,
+        &headers,
+        &sender_bytes,
+        query.chain_id,
+        SignedAction::ResumeGroup(&group_bytes),
+    )
+    .await?;
+    let chain_id = query
+        .chain_id
+        .ok_or_else(|| ApiError::bad_request("chainId is required"))?;
+
+    let records = db::resume_group(&state.db, &sender_bytes, &group_bytes, chain_id)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    if records.is_empty() {
+        return Err(ApiError::not_found("no paused transactions in group"));
+    }
+
+    scheduler::schedule_records(&state, &records)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    let mut tx_hashes = Vec::with_capacity(records.len());
+    for record in &records {
+        events::publish(&state, record).await;
+        tx_hashes.push(bytes_to_hex(&record.tx_hash));
+    }
+    info!(%chain_id, %group_id, resumed = records.len(), "group resumed");
+
+    Ok(Json(ResumeResponse {
+        resumed: records.len(),
+        tx_hashes,
+    }))
+}
+
+/// Sender and group webhooks must be authorized by the sender, like a cancel, since they report
+/// all of the sender's activity.
+async fn create_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookInfo>), ApiError> {
+    if state.rpcs.chain(payload.chain_id).is_none() {
+        return Err(ApiError::bad_request(format!(
+            "unsupported chainId {}",
+            payload.chain_id
+        )));
+    }
+
+    let sender = match payload.sender {
+        Some(value) => Some(parse_fixed_hex(&value, 20)?),
+        None => None,
+    };
+    let group_id = match payload.group_id {
+        Some(value) => Some(parse_fixed_hex(&value, 16)?),
+        None => None,
+    };
+    let tx_hash = match payload.tx_hash {
+        Some(value) => Some(parse_fixed_hex(&value, 32)?),
+        None => None,
+    };
+    match (&sender, &group_id, &tx_hash) {
+        (Some(_), _, None) | (None, None, Some(_)) => {}
+        (None, Some(_), None) => {
+            return Err(ApiError::bad_request("groupId requires sender"));
+        }
+        _ => {
+            return Err(ApiError::bad_request(
+                "specify either sender (optionally with groupId) or txHash",
+            ));
+        }
+    }
+
+    let allowed_hosts = state.config.current().webhooks.allowed_hosts.clone();
+    webhooks::check_target(&payload.url, &allowed_hosts)
+        .await
+        .map_err(ApiError::bad_request)?;
+    if let Some(sender) = &sender {
+        auth::authorize(
+            &state,
+            &headers,
+            sender,
+            Some(payload.chain_id),
+            SignedAction::RegisterWebhook {
+                group_id: group_id.as_deref(),
+                url: &payload.url,
+            },
+        )
+        .await?;
+    }
+
+    let record = db::insert_webhook(
+        &state.db,
+        &NewWebhook {
+            url: payload.url,
+            secret: webhooks::generate_secret(),
+            chain_id: PgU64::from(payload.chain_id),
+            sender,
+            group_id,
+            tx_hash,
+        },
+    )
+    .await
+    .map_err(|err| ApiError::internal(err.to_string()))?;
+
+    info!(webhook_id = %record.id, url = %record.url, "webhook registered");
+
+    Ok((StatusCode::CREATED, Json(webhook_info_from(&record, true))))
+}
+
+/// Reading or deleting a webhook needs the same signature as registering it. Transaction
+/// webhooks are managed by the sender of the watched transaction.
+async fn get_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookInfo>, ApiError> {
+    let record = db::get_webhook(&state.db, id)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?
+        .ok_or_else(|| ApiError::not_found("webhook not found"))?;
+    authorize_webhook(
+        &state,
+        &headers,
+        &record,
+        SignedAction::GetWebhook(id.as_bytes()),
+    )
+    .await?;
+
+    Ok(Json(webhook_info_from(&record, false)))
+}
+
+async fn delete_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let record = db::get_webhook(&state.db, id)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?
+        .ok_or_else(|| ApiError::not_found("webhook not found"))?;
+    authorize_webhook(
+        &state,
+        &headers,
+        &record,
+        SignedAction::DeleteWebhook(id.as_bytes()),
+    )
+    .await?;
+
+    let deleted = db::delete_webhook(&state.db, id)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    if !deleted {
+        return Err(ApiError::not_found("webhook not found"));
+    }
+    info!(webhook_id = %id, "webhook deleted");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn authorize_webhook(
+    state: &AppState,
+    headers: &HeaderMap,
+    record: &WebhookRecord,
+    action: SignedAction<'_>,
+) -> Result<(), ApiError> {
+    let chain_id = record.chain_id.to_uint();
+    let sender = match (&record.sender, &record.tx_hash) {
+        (Some(sender), _) => sender.clone(),
+        (None, Some(tx_hash)) => db::get_tx_by_hash(&state.db, Some(chain_id), tx_hash)
+            .await
+            .map_err(|err| ApiError::internal(err.to_string()))?
+            .map(|tx| tx.sender)
+            .ok_or_else(|| ApiError::unauthorized("webhook transaction is not stored"))?,
+        (None, None) => return Err(ApiError::internal("webhook has no filter")),
+    };
+    auth::authorize(state, headers, &sender, Some(chain_id), action).await?;
+
+    Ok(())
+}
+
+fn webhook_info_from(record: &WebhookRecord, include_secret: bool) -> WebhookInfo {
+    WebhookInfo {
+        id: record.id,
+        url: record.url.clone(),
+        chain_id: record.chain_id.to_uint(),
+        sender: record.sender.as_ref().map(|value| bytes_to_hex(value)),
+        group_id: record.group_id.as_ref().map(|value| bytes_to_hex(value)),
+        tx_hash: record.tx_hash.as_ref().map(|value| bytes_to_hex(value)),
+        secret: include_secret.then(|| record.secret.clone()),
+        created_at: record.created_at.timestamp(),
+    }
+}
+
+async fn stream_events(
+    State(state): State<AppState>,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let filter = event_filter_from(query)?;
+    let stream =
+        BroadcastStream::new(state.events.subscribe()).filter_map(move |item| match item {
+            Ok(event) if filter.matches(&event) => sse_event(&event).map(Ok),
+            Ok(_) => None,
+            Err(err) => {
+                warn!(error = %err, "event stream lagged");
+                None
+            }
+        });
+    // End the stream on shutdown so graceful shutdown doesn't wait on open subscriptions.
+    let shutdown = state.shutdown.clone();
+    let stream =
+        futures_util::StreamExt::take_until(stream, async move { shutdown.triggered().await });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+async fn stream_events_ws(
+    State(state): State<AppState>,
+    Query(query): Query<EventStreamQuery>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let filter = event_filter_from(query)?;
+    let receiver = state.events.subscribe();
+    let shutdown = state.shutdown.clone();
+
+    Ok(ws.on_upgrade(move |socket| forward_events(socket, receiver, filter, shutdown)))
+}
+
+async fn forward_events(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<TxEvent>,
+    filter: EventFilter,
+    shutdown: Shutdown,
+) {
+    loop {
+        tokio::select! {
+            _ = shutdown.triggered() => break,
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if !filter.matches(&event) {
+                        continue;
+                    }
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "event stream lagged");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+fn event_filter_from(query: EventStreamQuery) -> Result<EventFilter, ApiError> {
+    let normalize = |value: Option<String>, len: usize| -> Result<Option<String>, ApiError> {
+        value
+            .map(|value| parse_fixed_hex(&value, len).map(|bytes| bytes_to_hex(&bytes)))
+            .transpose()
+    };
+
+    Ok(EventFilter {
+        chain_id: query.chain_id,
+        sender: normalize(query.sender, 20)?,
+        group_id: normalize(query.group_id, 16)?,
+        tx_hash: normalize(query.tx_hash, 32)?,
+    })
+}
+
+fn sse_event(event: &TxEvent) -> Option<Event> {
+    Event::default()
+        .event(event.event_type.as_str())
+        .id(event.id.to_string())
+        .json_data(event)
+        .ok()
+}
+
+/// Authenticates `x-api-key` and takes a token from the key's per-minute bucket. Requests
+/// without a key pass through unless `api.require_api_key` is set.
+async fn authenticate(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let api_key = match request.headers().get(api_keys::API_KEY_HEADER) {
+        Some(value) => {
+            let value = value
+                .to_str()
+                .map_err(|_| ApiError::unauthorized("invalid api key"))?;
+            db::get_active_api_key_by_hash(&state.db, &api_keys::hash_key(value))
+                .await
+                .map_err(|err| ApiError::internal(err.to_string()))?
+                .ok_or_else(|| ApiError::unauthorized("invalid api key"))?
+        }
+        None if state.config.current().api.require_api_key => {
+            return Err(ApiError::unauthorized("missing api key"));
+        }
+        None => return Ok(next.run(request).await),
+    };
+
+    let quota = api_keys::take_request(&state, &api_key)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    if let Quota::Limited { retry_after_secs } = quota {
+        return Err(ApiError::too_many_requests(
+            "rate limit exceeded",
+            retry_after_secs,
+        ));
+    }
+
+    request.extensions_mut().insert(api_key);
+    Ok(next.run(request).await)
+}
+
+/// Applies the key's chain/sender restrictions and its per-sender queue limit to a submission.
+async fn check_api_key_limits(
+    state: &AppState,
+    api_key: &ApiKeyRecord,
+    prepared: &[NewTx],
+) -> Result<(), ApiError> {
+    let mut per_sender: BTreeMap<(u64, Vec<u8>), i64> = BTreeMap::new();
+    for new_tx in prepared {
+        let chain_id = new_tx.chain_id.to_uint();
+        if !api_key.allows_chain(chain_id) {
+            return Err(ApiError::forbidden(format!(
+                "api key not allowed for chainId {chain_id}"
+            )));
+        }
+        if !api_key.allows_sender(&new_tx.sender) {
+            return Err(ApiError::forbidden(format!(
+                "api key not allowed for sender {}",
+                bytes_to_hex(&new_tx.sender)
+            )));
+        }
+        *per_sender
+            .entry((chain_id, new_tx.sender.clone()))
+            .or_default() += 1;
+    }
+
+    for ((chain_id, sender), count) in per_sender {
+        let pending = db::count_pending_txs_for_sender(&state.db, chain_id, &sender)
+            .await
+            .map_err(|err| ApiError::internal(err.to_string()))?;
+        if pending + count > i64::from(api_key.max_queued_per_sender) {
+            return Err(ApiError::too_many_requests(
+                format!(
+                    "sender {} has too many queued transactions",
+                    bytes_to_hex(&sender)
+                ),
+                QUEUED_LIMIT_RETRY_AFTER_SECS,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+async fn take_api_key_quota(
+    state: &AppState,
+    api_key: &ApiKeyRecord,
+    count: usize,
+) -> Result<(), ApiError> {
+    let quota = api_keys::take_daily_txs(state, api_key, count)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    if let Quota::Limited { retry_after_secs } = quota {
+        return Err(ApiError::too_many_requests(
+            "daily transaction quota exceeded",
+            retry_after_secs,
+        ));
+    }
+
+    Ok(())
+}
+
+/// The (chain, sender, nonce key, nonce) a transaction occupies.
+fn nonce_slot(record: &TxRecord) -> (u64, Vec<u8>, Vec<u8>, u64) {
+    (
+        record.chain_id.to_uint(),
+        record.sender.clone(),
+        record.nonce_key.clone(),
+        record.nonce.to_uint(),
+    )
+}
+
+/// A fee ladder's rungs with its escalation thresholds (attempts, seconds).
+type PreparedLadder = (Vec<NewTx>, i32, i64);
+/// A stored fee ladder's id and rungs, and whether it was already known.
+type StoredLadder = (Uuid, Vec<TxRecord>, bool);
+
+/// Stores a submission's transactions and fee ladders in one database transaction, so a
+/// rejected ladder leaves nothing behind. The key's daily quota is only consumed for newly
+/// stored nonces once every other check has passed, and is refunded if the commit fails.
+async fn store_submission(
+    state: &AppState,
+    api_key: Option<&ApiKeyRecord>,
+    prepared: Vec<NewTx>,
+    ladders: Vec<PreparedLadder>,
+    replace: bool,
+) -> Result<(Vec<TxRecord>, Vec<bool>, Vec<StoredLadder>), ApiError> {
+    // A ladder occupies a single nonce, so only its first rung counts against the quotas.
+    let mut limited = prepared.clone();
+    limited.extend(ladders.iter().map(|(rungs, _, _)| rungs[0].clone()));
+    if let Some(api_key) = api_key {
+        check_api_key_limits(state, api_key, &limited).await?;
+    }
+
+    let bump_percent = state.config.current().api.replacement_bump_percent;
+    let mut db_tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+
+    // Fee ladder rungs go through the same group checks as plain transactions.
+    let all_txs: Vec<&NewTx> = prepared
+        .iter()
+        .chain(ladders.iter().flat_map(|(rungs, _, _)| rungs))
+        .collect();
+    validate_groups(&mut db_tx, &all_txs).await?;
+
+    let (records, already_known_flags, mut replaced) =
+        insert_transactions(&mut db_tx, prepared, replace, bump_percent).await?;
+    let mut stored_ladders = Vec::with_capacity(ladders.len());
+    for (rungs, escalate_after_attempts, escalate_after_seconds) in ladders {
+        let (ladder, mut ladder_replaced) = insert_fee_ladder(
+            &mut db_tx,
+            rungs,
+            escalate_after_attempts,
+            escalate_after_seconds,
+            replace,
+            bump_percent,
+        )
+        .await?;
+        replaced.append(&mut ladder_replaced);
+        stored_ladders.push(ladder);
+    }
+
+    // Only newly stored nonces count: duplicates and replacements of a pending nonce are free.
+    let replaced_nonces: BTreeSet<_> = replaced.iter().map(nonce_slot).collect();
+    let stored = records
+        .iter()
+        .zip(already_known_flags.iter())
+        .filter(|(_, already_known)| !**already_known)
+        .map(|(record, _)| record)
+        .chain(
+            stored_ladders
+                .iter()
+                .filter(|(_, _, already_known)| !already_known)
+                .filter_map(|(_, ladder_records, _)| ladder_records.first()),
+        )
+        .filter(|record| !replaced_nonces.contains(&nonce_slot(record)))
+        .count();
+    let quota_taken = match api_key {
+        Some(api_key) if stored > 0 => {
+            take_api_key_quota(state, api_key, stored).await?;
+            Some(api_key)
+        }
+        _ => None,
+    };
+    if let Err(err) = db_tx.commit().await {
+        if let Some(api_key) = quota_taken
+            && let Err(refund_err) = api_keys::refund_daily_txs(state, api_key, stored).await
+        {
+            warn!(api_key = %api_key.id, error = %refund_err, "failed to refund daily quota");
+        }
+        return Err(ApiError::internal(err.to_string()));
+    }
+
+    scheduler::schedule_records(state, &records)
+        .await
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    for (_, ladder_records, already_known) in &stored_ladders {
+        if !already_known {
+            scheduler::schedule_records(state, ladder_records)
+                .await
+                .map_err(|err| ApiError::internal(err.to_string()))?;
+        }
+    }
+    finish_replacements(state, &replaced).await;
+
+    for (record, already_known) in records.iter().zip(already_known_flags.iter()) {
+        if *already_known {
+            continue;
+        }
+        telemetry::record_ingested(record.chain_id.to_uint());
+        if record.status.as_str() != TxStatus::Queued.as_str() {
+            continue;
+        }
+        info!(
+            chain_id = %record.chain_id.to_uint(),
+            tx_hash = %bytes_to_hex(&record.tx_hash),
+            eligible_at = record.eligible_at.timestamp(),
+            expires_at = ?record.expires_at.map(|ts| ts.timestamp()),
+            "transaction queued",
+        );
+    }
+    for (ladder_id, ladder_records, already_known) in &stored_ladders {
+        if *already_known {
+            continue;
+        }
+        for record in ladder_records {
+            telemetry::record_ingested(record.chain_id.to_uint());
+            info!(
+                chain_id = %record.chain_id.to_uint(),
+                tx_hash = %bytes_to_hex(&record.tx_hash),
+                %ladder_id,
+                rung = ?record.ladder_rung,
+                status = %record.status,
+                "fee ladder rung queued",
+            );
+        }
+    }
+
+    Ok((records, already_known_flags, stored_ladders))
+}
+
+/// Checks that group transactions share their group's nonce_key and that valid_before follows
+/// nonce order, across the submission and the group's stored transactions.
+async fn validate_groups(
+    db_tx: &mut Transaction<'_, Postgres>,
+    txs: &[&NewTx],
+) -> Result<(), ApiError> {
+    type GroupKey = (u64, Vec<u8>, Vec<u8>);
+    type NonceWindow = (u64, Option<u64>);
+
+    let mut group_nonce_keys: BTreeMap<GroupKey, Vec<u8>> = BTreeMap::new();
+    let mut group_windows: BTreeMap<GroupKey, Vec<NonceWindow>> = BTreeMap::new();
+    for new_tx in txs {
+        let Some(group_id) = new_tx.group_id.as_ref() else {
+            continue;
+        };
+        let key = (
+            new_tx.chain_id.to_uint(),
+            new_tx.sender.clone(),
+            group_id.clone(),
+        );
+        if let Some(existing) = group_nonce_keys.get(&key) {
+            if existing != &new_tx.nonce_key {
+                return Err(ApiError::bad_request(
+                    "group transactions must share the same nonce_key",
+                ));
+            }
+        } else {
+            group_nonce_keys.insert(key.clone(), new_tx.nonce_key.clone());
+        }
+        group_windows.entry(key).or_default().push((
+            new_tx.nonce.to_uint(),
+            new_tx.valid_before.as_ref().map(|value| value.to_uint()),
+        ));
     }
 
     for ((chain_id, sender, group_id), nonce_key) in &group_nonce_keys {
-        let existing = db::get_group_nonce_key(&mut db_tx, *chain_id, sender, group_id)
+        let existing = db::get_group_nonce_key(db_tx, *chain_id, sender, group_id)
             .await
             .map_err(|err| ApiError::internal(err.to_string()))?;
         if let Some(existing) = existing
@@ -1058,7 +2141,7 @@ async fn store_transactions(
     }
 
     for ((chain_id, sender, group_id), mut windows) in group_windows {
-        let existing = db::get_group_nonce_windows(&mut db_tx, chain_id, &sender, &group_id)
+        let existing = db::get_group_nonce_windows(db_tx, chain_id, &sender, &group_id)
             .await
             .map_err(|err| ApiError::internal(err.to_string()))?;
         for row in existing {
@@ -1070,42 +2153,217 @@ async fn store_transactions(
         validate_nonce_valid_before_order(&windows)?;
     }
 
+    Ok(())
+}
+
+/// Inserts plain transactions, returning the stored records, whether each was already known,
+/// and the transactions they replaced.
+async fn insert_transactions(
+    db_tx: &mut Transaction<'_, Postgres>,
+    prepared: Vec<NewTx>,
+    replace: bool,
+    bump_percent: u64,
+) -> Result<(Vec<TxRecord>, Vec<bool>, Vec<TxRecord>), ApiError> {
     let mut records = Vec::with_capacity(prepared.len());
     let mut already_known_flags = Vec::with_capacity(prepared.len());
+    let mut replaced = Vec::new();
     for new_tx in prepared {
-        let (record, already_known) = db::insert_tx(&mut db_tx, &new_tx)
+        let existing = if replace {
+            db::find_replaceable_tx(db_tx, &new_tx)
+                .await
+                .map_err(|err| ApiError::internal(err.to_string()))?
+        } else {
+            None
+        };
+        if let Some(existing) = &existing {
+            reject_paused_replacement(existing)?;
+            let existing_fee = recorded_max_fee_per_gas(existing)?;
+            check_replacement_fee(existing_fee, new_tx.max_fee_per_gas.to_uint(), bump_percent)?;
+        }
+
+        let (record, already_known) = db::insert_tx(db_tx, &new_tx)
             .await
             .map_err(|err| ApiError::internal(err.to_string()))?;
+        if !already_known && let Some(existing) = existing {
+            let mut updated = db::mark_replaced(db_tx, existing.id, &record.tx_hash)
+                .await
+                .map_err(|err| ApiError::internal(err.to_string()))?;
+            replaced.append(&mut updated);
+        }
         records.push(record);
         already_known_flags.push(already_known);
     }
 
-    db_tx
-        .commit()
-        .await
-        .map_err(|err| ApiError::internal(err.to_string()))?;
-
-    scheduler::schedule_records(state, &records)
+    Ok((records, already_known_flags, replaced))
+}
+
+/// Inserts the rungs of one fee ladder. Only the cheapest rung is queued; the rest wait on
+/// `standby` until the scheduler escalates to them. Resubmitting a stored ladder returns it as
+/// already known.
+async fn insert_fee_ladder(
+    db_tx: &mut Transaction<'_, Postgres>,
+    mut rungs: Vec<NewTx>,
+    escalate_after_attempts: i32,
+    escalate_after_seconds: i64,
+    replace: bool,
+    bump_percent: u64,
+) -> Result<(StoredLadder, Vec<TxRecord>), ApiError> {
+    let chain_id = rungs[0].chain_id.to_uint();
+    let known = db::find_tx_by_hash(db_tx, chain_id, &rungs[0].tx_hash)
         .await
         .map_err(|err| ApiError::internal(err.to_string()))?;
+    if let Some(known) = known {
+        let ladder_id = known.ladder_id.ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "transaction {} was already submitted outside a fee ladder",
+                bytes_to_hex(&known.tx_hash)
+            ))
+        })?;
+        let records = db::list_fee_ladder_txs(db_tx, ladder_id)
+            .await
+            .map_err(|err| ApiError::internal(err.to_string()))?;
+        return Ok(((ladder_id, records, true), Vec::new()));
+    }
 
-    for (record, already_known) in records.iter().zip(already_known_flags.iter()) {
-        if *already_known {
-            continue;
+    let ladder_id = Uuid::new_v4();
+    for (rung, new_tx) in rungs.iter_mut().enumerate() {
+        new_tx.ladder_id = Some(ladder_id);
+        new_tx.ladder_rung =
+            Some(i32::try_from(rung).map_err(|_| ApiError::bad_request("too many rungs"))?);
+        if rung > 0 {
+            new_tx.status = TxStatus::Standby.as_str().to_string();
         }
-        if record.status.as_str() != TxStatus::Queued.as_str() {
-            continue;
+    }
+
+    let existing = if replace {
+        db::find_replaceable_tx(db_tx, &rungs[0])
+            .await
+            .map_err(|err| ApiError::internal(err.to_string()))?
+    } else {
+        None
+    };
+    if let Some(existing) = &existing {
+        reject_paused_replacement(existing)?;
+        check_replacement_fee(
+            recorded_max_fee_per_gas(existing)?,
+            rungs[0].max_fee_per_gas.to_uint(),
+            bump_percent,
+        )?;
+    }
+
+    db::insert_fee_ladder(
+        db_tx,
+        ladder_id,
+        chain_id,
+        escalate_after_attempts,
+        escalate_after_seconds,
+    )
+    .await
+    .map_err(|err| ApiError::internal(err.to_string()))?;
+
+    let mut records = Vec::with_capacity(rungs.len());
+    for new_tx in &rungs {
+        let (record, already_known) = db::insert_tx(db_tx, new_tx)
+            .await
+            .map_err(|err| ApiError::internal(err.to_string()))?;
+        if already_known {
+            return Err(ApiError::bad_request(format!(
+                "transaction {} was already submitted outside this fee ladder",
+                bytes_to_hex(&record.tx_hash)
+            )));
         }
+        records.push(record);
+    }
+
+    let mut replaced = Vec::new();
+    if let Some(existing) = existing {
+        replaced = db::mark_replaced(db_tx, existing.id, &records[0].tx_hash)
+            .await
+            .map_err(|err| ApiError::internal(err.to_string()))?;
+    }
+
+    Ok(((ladder_id, records, false), replaced))
+}
+
+/// Takes replaced transactions out of the schedule and notifies subscribers.
+async fn finish_replacements(state: &AppState, replaced: &[TxRecord]) {
+    let _ = scheduler::unschedule_records(state, replaced).await;
+    for record in replaced {
         info!(
             chain_id = %record.chain_id.to_uint(),
             tx_hash = %bytes_to_hex(&record.tx_hash),
-            eligible_at = record.eligible_at.timestamp(),
-            expires_at = ?record.expires_at.map(|ts| ts.timestamp()),
-            "transaction queued",
+            replaced_by = ?record.replaced_by.as_ref().map(|value| bytes_to_hex(value)),
+            "transaction replaced",
         );
+        events::publish(state, record).await;
+    }
+}
+
+/// Rungs must be variants of the same nonce, each priced high enough to replace the one below it.
+fn validate_fee_ladder(rungs: &[NewTx], bump_percent: u64) -> Result<(), ApiError> {
+    if rungs.len() < 2 {
+        return Err(ApiError::bad_request("fee ladder needs at least two rungs"));
+    }
+    let first = &rungs[0];
+    for pair in rungs.windows(2) {
+        let (lower, higher) = (&pair[0], &pair[1]);
+        if higher.sender != first.sender
+            || higher.nonce_key != first.nonce_key
+            || higher.nonce.to_uint() != first.nonce.to_uint()
+        {
+            return Err(ApiError::bad_request(
+                "fee ladder rungs must share sender, nonce_key and nonce",
+            ));
+        }
+        check_replacement_fee(
+            lower.max_fee_per_gas.to_uint(),
+            higher.max_fee_per_gas.to_uint(),
+            bump_percent,
+        )?;
     }
+    Ok(())
+}
 
-    Ok((records, already_known_flags))
+/// Rows stored before fees were recorded fall back to decoding `raw_tx`.
+fn recorded_max_fee_per_gas(record: &TxRecord) -> Result<u128, ApiError> {
+    if let Some(fee) = &record.max_fee_per_gas {
+        return Ok(fee.to_uint());
+    }
+    record
+        .raw_tx
+        .as_deref()
+        .and_then(|raw_tx| parse_raw_tx(&bytes_to_hex(raw_tx)).ok())
+        .map(|parsed| parsed.max_fee_per_gas)
+        .ok_or_else(|| ApiError::internal("unknown fee for pending transaction"))
+}
+
+/// A paused transaction stays in place until its group is resumed, so that resuming never
+/// broadcasts both it and a replacement.
+fn reject_paused_replacement(existing: &TxRecord) -> Result<(), ApiError> {
+    if existing.status == TxStatus::Paused.as_str() {
+        return Err(ApiError::bad_request(format!(
+            "transaction {} is paused; resume its group before replacing it",
+            bytes_to_hex(&existing.tx_hash)
+        )));
+    }
+    Ok(())
+}
+
+fn check_replacement_fee(
+    existing_fee: u128,
+    new_fee: u128,
+    bump_percent: u64,
+) -> Result<(), ApiError> {
+    let required = existing_fee
+        .saturating_mul(100 + u128::from(bump_percent))
+        .div_ceil(100)
+        .max(existing_fee.saturating_add(1));
+    if new_fee < required {
+        return Err(ApiError::bad_request(format!(
+            "replacement underpriced: max_fee_per_gas {new_fee} is below {required} ({bump_percent}% bump over pending transaction)"
+        )));
+    }
+    Ok(())
 }
 
 fn validate_nonce_valid_before_order(pairs: &[(u64, Option<u64>)]) -> Result<(), ApiError> {
@@ -1116,7 +2374,7 @@ fn validate_nonce_valid_before_order(pairs: &[(u64, Option<u64>)]) -> Result<(),
     if ordered.len() <= 1 {
         return Ok(());
     }
-    ordered.sort_by_key(|(nonce, _)| *nonce);
+    ordered.sort();
     let mut prev = ordered[0].1;
     for (_, valid_before) in ordered.into_iter().skip(1) {
         if valid_before < prev {
@@ -1129,6 +2387,12 @@ fn validate_nonce_valid_before_order(pairs: &[(u64, Option<u64>)]) -> Result<(),
     Ok(())
 }
 
+/// Renders `record` the way `GET /v1/transactions/{txHash}` does.
+pub fn tx_json(record: &TxRecord) -> anyhow::Result<serde_json::Value> {
+    let info = tx_info_from(record).map_err(|err| anyhow::anyhow!(err.message))?;
+    Ok(serde_json::to_value(info)?)
+}
+
 fn tx_info_from(record: &TxRecord) -> Result<TxInfo, ApiError> {
     let parsed = match record.raw_tx.as_deref() {
         Some(raw_tx) => {
@@ -1172,9 +2436,20 @@ fn tx_info_from(record: &TxRecord) -> Result<TxInfo, ApiError> {
         last_error: record.last_error.clone(),
         last_broadcast_at: record.last_broadcast_at.map(|ts| ts.timestamp()),
         receipt: record.receipt.clone(),
+        reverted: record.receipt_success.map(|success| !success),
+        block_number: record.receipt_block_number.to_option_uint(),
+        gas_used: record.receipt_gas_used.to_option_uint(),
+        effective_gas_price: record.receipt_effective_gas_price.to_option_uint(),
+        fee_token: record
+            .receipt_fee_token
+            .as_ref()
+            .map(|value| bytes_to_hex(value)),
+        replaced_by: record.replaced_by.as_ref().map(|value| bytes_to_hex(value)),
+        ladder_id: record.ladder_id.map(|value| value.to_string()),
+        ladder_rung: record.ladder_rung,
         gas,
         gas_price,
-        max_fee_per_gas,
+        max_fee_per_gas: max_fee_per_gas.or(record.max_fee_per_gas.to_option_uint()),
         max_priority_fee_per_gas,
         input,
         calls,
@@ -1212,7 +2487,7 @@ async fn build_cancel_plan(
 
     let sender_addr = parse_address(sender)?;
 
-    let current_nonce = fetch_current_nonce(chain, sender_addr, &nonce_key_bytes).await?;
+    let current_nonce = fetch_current_nonce(&chain, sender_addr, &nonce_key_bytes).await?;
     let max_nonce = *nonces.last().unwrap_or(&0);
 
     Ok(CancelPlan {
@@ -1224,12 +2499,111 @@ async fn build_cancel_plan(
     })
 }
 
-fn ready_key(chain_id: u64) -> String {
-    format!("watchtower:ready:{chain_id}")
+/// `fromNonce`/`toNonce` as an inclusive range, open-ended on the side that is missing.
+fn parse_nonce_range(
+    from_nonce: Option<u64>,
+    to_nonce: Option<u64>,
+) -> Result<Option<RangeInclusive<u64>>, ApiError> {
+    if from_nonce.is_none() && to_nonce.is_none() {
+        return Ok(None);
+    }
+    let from_nonce = from_nonce.unwrap_or(0);
+    let to_nonce = to_nonce.unwrap_or(u64::MAX);
+    if from_nonce > to_nonce {
+        return Err(ApiError::bad_request("fromNonce must not exceed toNonce"));
+    }
+    Ok(Some(from_nonce..=to_nonce))
+}
+
+fn parse_page(
+    sort: Option<&str>,
+    order: Option<&str>,
+    cursor: Option<&str>,
+) -> Result<(db::SortKey, db::SortOrder, Option<db::PageCursor>), ApiError> {
+    let sort = match sort {
+        Some(value) => db::SortKey::try_from(value)
+            .map_err(|_| ApiError::bad_request(format!("invalid sort: {value}")))?,
+        None => db::SortKey::default(),
+    };
+    let order = match order {
+        Some(value) => db::SortOrder::try_from(value)
+            .map_err(|_| ApiError::bad_request(format!("invalid order: {value}")))?,
+        None => db::SortOrder::default(),
+    };
+    let after = match cursor {
+        Some(value) => {
+            let cursor =
+                decode_cursor(value).ok_or_else(|| ApiError::bad_request("invalid cursor"))?;
+            if cursor.sort != sort || cursor.order != order {
+                return Err(ApiError::bad_request(
+                    "cursor was issued for a different sort or order",
+                ));
+            }
+            Some(cursor)
+        }
+        None => None,
+    };
+    Ok((sort, order, after))
 }
 
-fn retry_key(chain_id: u64) -> String {
-    format!("watchtower:retry:{chain_id}")
+fn parse_time_filter(value: Option<i64>, name: &str) -> Result<Option<DateTime<Utc>>, ApiError> {
+    value
+        .map(|secs| {
+            DateTime::from_timestamp(secs, 0)
+                .ok_or_else(|| ApiError::bad_request(format!("invalid {name}")))
+        })
+        .transpose()
+}
+
+/// Trims the extra row the list queries fetch and returns the cursor for the next page, if any.
+fn next_page<T>(
+    items: &mut Vec<T>,
+    limit: i64,
+    cursor_of: impl Fn(&T) -> db::PageCursor,
+) -> Option<String> {
+    let limit = usize::try_from(limit).unwrap_or(0);
+    if items.len() <= limit {
+        return None;
+    }
+    items.truncate(limit);
+    items.last().map(|item| encode_cursor(&cursor_of(item)))
+}
+
+/// Cursors are opaque to clients: `sort:order:value:id`, hex encoded. Timestamps are kept in
+/// microseconds so the keyset comparison is exact.
+fn encode_cursor(cursor: &db::PageCursor) -> String {
+    let value = match &cursor.value {
+        db::SortValue::Timestamp(value) => value.timestamp_micros().to_string(),
+        db::SortValue::Number(value) => value.to_string(),
+    };
+    hex::encode(format!(
+        "{}:{}:{value}:{}",
+        cursor.sort.as_str(),
+        cursor.order.as_str(),
+        cursor.id
+    ))
+}
+
+fn decode_cursor(value: &str) -> Option<db::PageCursor> {
+    let decoded = String::from_utf8(hex::decode(value).ok()?).ok()?;
+    let mut parts = decoded.split(':');
+    let sort = db::SortKey::try_from(parts.next()?).ok()?;
+    let order = db::SortOrder::try_from(parts.next()?).ok()?;
+    let raw_value = parts.next()?;
+    let id = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let value = match sort {
+        db::SortKey::Nonce => db::SortValue::Number(raw_value.parse().ok()?),
+        _ => db::SortValue::Timestamp(DateTime::from_timestamp_micros(raw_value.parse().ok()?)?),
+    };
+    Some(db::PageCursor {
+        sort,
+        order,
+        value,
+        id,
+    })
 }
 
 fn parse_fixed_hex(value: &str, len: usize) -> Result<Vec<u8>, ApiError> {
@@ -1251,43 +2625,6 @@ fn parse_hex(value: &str) -> Result<Vec<u8>, ApiError> {
     hex::decode(value).map_err(|err| ApiError::bad_request(err.to_string()))
 }
 
-fn verify_group_signature(
-    headers: &HeaderMap,
-    sender_bytes: &[u8],
-    group_bytes: &[u8],
-) -> Result<(), ApiError> {
-    let signature_value = headers
-        .get(GROUP_SIGNATURE_HEADER)
-        .ok_or_else(|| ApiError::unauthorized("missing authorization header"))?;
-    let signature_str = signature_value
-        .to_str()
-        .map_err(|_| ApiError::unauthorized("invalid authorization header"))?;
-    let mut parts = signature_str.split_whitespace();
-    let scheme = parts
-        .next()
-        .ok_or_else(|| ApiError::unauthorized("invalid authorization header"))?;
-    let signature_hex = parts
-        .next()
-        .ok_or_else(|| ApiError::unauthorized("invalid authorization header"))?;
-    if scheme != "Signature" || parts.next().is_some() {
-        return Err(ApiError::unauthorized("invalid authorization header"));
-    }
-    let signature_bytes =
-        parse_hex(signature_hex).map_err(|_| ApiError::unauthorized("invalid signature"))?;
-    let signature = PrimitiveSignature::from_bytes(&signature_bytes)
-        .map_err(|_| ApiError::unauthorized("invalid signature"))?;
-    let group_hash = keccak256(group_bytes);
-    let recovered = signature
-        .recover_signer(&group_hash)
-        .map_err(|_| ApiError::unauthorized("invalid signature"))?;
-    let sender_addr =
-        parse_address(sender_bytes).map_err(|err| ApiError::bad_request(err.to_string()))?;
-    if recovered != sender_addr {
-        return Err(ApiError::unauthorized("signature does not match sender"));
-    }
-    Ok(())
-}
-
 fn bytes_to_hex(bytes: &[u8]) -> String {
     format!("0x{}", hex::encode(bytes))
 }
@@ -1357,40 +2694,6 @@ fn parse_address(bytes: &[u8]) -> anyhow::Result<alloy::primitives::Address> {
     Ok(alloy::primitives::Address::from(data))
 }
 
-async fn fetch_current_nonce(
-    chain: &crate::rpc::ChainRpc,
-    sender: alloy::primitives::Address,
-    nonce_key_bytes: &[u8],
-) -> anyhow::Result<Option<u64>> {
-    let nonce_key = u256_from_bytes(nonce_key_bytes).map_err(|err| anyhow::anyhow!(err.message))?;
-    if nonce_key.is_zero() {
-        let provider = chain
-            .http
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("missing provider"))?;
-        let nonce = provider.get_transaction_count(sender).await?;
-        return Ok(Some(nonce));
-    }
-
-    let call = tempo_alloy::contracts::precompiles::INonce::getNonceCall {
-        account: sender,
-        nonceKey: nonce_key,
-    };
-    let mut req = tempo_alloy::rpc::TempoTransactionRequest::default();
-    req.set_kind(alloy::primitives::TxKind::Call(nonce_precompile_address()));
-    req.set_call(&call);
-
-    let provider = chain
-        .http
-        .first()
-        .ok_or_else(|| anyhow::anyhow!("missing provider"))?;
-    let output = provider
-        .call(req)
-        .decode_resp::<tempo_alloy::contracts::precompiles::INonce::getNonceCall>()
-        .await??;
-    Ok(Some(output))
-}
-
 fn group_id_from_nonce_key(nonce_key_bytes: &[u8]) -> Vec<u8> {
     let hash = keccak256(nonce_key_bytes);
     let mut group_id = vec![0u8; 16];
@@ -1398,18 +2701,41 @@ fn group_id_from_nonce_key(nonce_key_bytes: &[u8]) -> Vec<u8> {
     group_id
 }
 
-fn nonce_precompile_address() -> alloy::primitives::Address {
-    alloy::primitives::Address::from_slice(
-        &hex::decode("4e4f4e4345000000000000000000000000000000").expect("valid precompile"),
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
-        parse_fixed_hex, u256_bytes_to_hex, u256_from_bytes, validate_nonce_valid_before_order,
+        check_replacement_fee, decode_cursor, encode_cursor, next_page, parse_fixed_hex,
+        parse_nonce_range, parse_page, u256_bytes_to_hex, u256_from_bytes, validate_fee_ladder,
+        validate_nonce_valid_before_order,
     };
+    use crate::db::{PageCursor, SortKey, SortOrder, SortValue};
+    use crate::models::NewTx;
     use alloy::primitives::U256;
+    use chrono::Utc;
+    use sqlx_pg_uint::{PgU64, PgU128};
+
+    fn rung(nonce: u64, max_fee_per_gas: u128) -> NewTx {
+        NewTx {
+            chain_id: PgU64::from(42431),
+            tx_hash: vec![nonce as u8; 32],
+            raw_tx: Vec::new(),
+            sender: vec![0x11; 20],
+            fee_payer: None,
+            nonce_key: vec![0; 32],
+            nonce: PgU64::from(nonce),
+            valid_after: None,
+            valid_before: None,
+            eligible_at: Utc::now(),
+            expires_at: None,
+            status: "queued".to_string(),
+            group_id: None,
+            next_action_at: Utc::now(),
+            max_fee_per_gas: PgU128::from(max_fee_per_gas),
+            ladder_id: None,
+            ladder_rung: None,
+            call_targets: Vec::new(),
+        }
+    }
 
     #[test]
     fn parse_fixed_hex_enforces_length() {
@@ -1427,6 +2753,35 @@ mod tests {
         assert_eq!(u256_bytes_to_hex(&[0x01]), "0x1");
     }
 
+    #[test]
+    fn check_replacement_fee_requires_bump() {
+        assert!(check_replacement_fee(100, 110, 10).is_ok());
+        let err = check_replacement_fee(100, 109, 10).expect_err("expected error");
+        assert!(err.message.contains("replacement underpriced"));
+        // A zero bump still requires a strictly higher fee.
+        assert!(check_replacement_fee(100, 100, 0).is_err());
+        assert!(check_replacement_fee(100, 101, 0).is_ok());
+        // Rounds the required fee up.
+        assert!(check_replacement_fee(15, 16, 10).is_err());
+        assert!(check_replacement_fee(15, 17, 10).is_ok());
+    }
+
+    #[test]
+    fn validate_fee_ladder_requires_one_nonce_and_rising_fees() {
+        assert!(validate_fee_ladder(&[rung(0, 100), rung(0, 110), rung(0, 121)], 10).is_ok());
+
+        let err = validate_fee_ladder(&[rung(0, 100)], 10).expect_err("expected error");
+        assert!(err.message.contains("at least two rungs"));
+
+        let err =
+            validate_fee_ladder(&[rung(0, 100), rung(1, 200)], 10).expect_err("expected error");
+        assert!(err.message.contains("must share"));
+
+        let err =
+            validate_fee_ladder(&[rung(0, 100), rung(0, 105)], 10).expect_err("expected error");
+        assert!(err.message.contains("replacement underpriced"));
+    }
+
     #[test]
     fn validate_nonce_valid_before_order_accepts_monotonic() {
         let ok = validate_nonce_valid_before_order(&[(1, Some(10)), (2, Some(10)), (3, Some(12))]);
@@ -1439,4 +2794,73 @@ mod tests {
             .expect_err("expected error");
         assert!(err.message.contains("valid_before order"));
     }
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = PageCursor {
+            sort: SortKey::UpdatedAt,
+            order: SortOrder::Asc,
+            value: SortValue::Timestamp(
+                chrono::DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            ),
+            id: 42,
+        };
+        assert_eq!(decode_cursor(&encode_cursor(&cursor)), Some(cursor));
+
+        let cursor = PageCursor {
+            sort: SortKey::Nonce,
+            order: SortOrder::Desc,
+            value: SortValue::Number(u64::MAX),
+            id: 7,
+        };
+        assert_eq!(decode_cursor(&encode_cursor(&cursor)), Some(cursor));
+
+        assert_eq!(decode_cursor("not-a-cursor"), None);
+        assert_eq!(decode_cursor(&hex::encode("nonce:desc:1")), None);
+    }
+
+    #[test]
+    fn parse_page_rejects_cursor_for_other_sort() {
+        let cursor = encode_cursor(&PageCursor {
+            sort: SortKey::Nonce,
+            order: SortOrder::Desc,
+            value: SortValue::Number(3),
+            id: 1,
+        });
+        assert!(parse_page(Some("nonce"), None, Some(&cursor)).is_ok());
+        let err = parse_page(None, None, Some(&cursor)).expect_err("expected error");
+        assert!(err.message.contains("different sort"));
+        assert!(parse_page(Some("priority"), None, None).is_err());
+    }
+
+    #[test]
+    fn nonce_range_is_open_ended_on_the_missing_side() {
+        assert_eq!(parse_nonce_range(None, None).unwrap(), None);
+        assert_eq!(
+            parse_nonce_range(Some(3), None).unwrap(),
+            Some(3..=u64::MAX)
+        );
+        assert_eq!(parse_nonce_range(None, Some(5)).unwrap(), Some(0..=5));
+        assert_eq!(parse_nonce_range(Some(4), Some(4)).unwrap(), Some(4..=4));
+        assert!(parse_nonce_range(Some(5), Some(4)).is_err());
+    }
+
+    #[test]
+    fn next_page_trims_extra_row() {
+        let cursor_of = |id: &i64| PageCursor {
+            sort: SortKey::Nonce,
+            order: SortOrder::Desc,
+            value: SortValue::Number(0),
+            id: *id,
+        };
+
+        let mut items = vec![5, 4, 3];
+        let next = next_page(&mut items, 2, cursor_of).expect("next page");
+        assert_eq!(items, vec![5, 4]);
+        assert_eq!(decode_cursor(&next).map(|cursor| cursor.id), Some(4));
+
+        let mut items = vec![5, 4];
+        assert!(next_page(&mut items, 2, cursor_of).is_none());
+        assert_eq!(items.len(), 2);
+    }
 }
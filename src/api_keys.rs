@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use redis::Script;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::models::ApiKeyRecord;
+use crate::state::AppState;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+const DAILY_QUOTA_TTL_SECONDS: i64 = 2 * 24 * 60 * 60;
+
+// Refills `capacity` tokens per minute; returns {allowed, wait_ms}.
+static TOKEN_BUCKET: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        -- token_bucket
+        local capacity = tonumber(ARGV[1])
+        local refill_per_ms = tonumber(ARGV[2])
+        local now_ms = tonumber(ARGV[3])
+        local cost = tonumber(ARGV[4])
+        local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+        local tokens = tonumber(bucket[1]) or capacity
+        local ts = tonumber(bucket[2]) or now_ms
+        tokens = math.min(capacity, tokens + math.max(0, now_ms - ts) * refill_per_ms)
+        local allowed = 0
+        local wait_ms = 0
+        if tokens >= cost then
+            tokens = tokens - cost
+            allowed = 1
+        else
+            wait_ms = math.ceil((cost - tokens) / refill_per_ms)
+        end
+        redis.call('HSET', KEYS[1], 'tokens', tokens, 'ts', now_ms)
+        redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / refill_per_ms) + 1000)
+        return {allowed, wait_ms}
+        "#,
+    )
+});
+
+// Adds `inc` to a counter unless that would exceed `limit`; returns {allowed, current}.
+static QUOTA_COUNTER: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        -- quota_counter
+        local limit = tonumber(ARGV[1])
+        local inc = tonumber(ARGV[2])
+        local ttl = tonumber(ARGV[3])
+        local current = tonumber(redis.call('GET', KEYS[1]) or '0')
+        if current + inc > limit then
+            return {0, current}
+        end
+        current = redis.call('INCRBY', KEYS[1], inc)
+        redis.call('EXPIRE', KEYS[1], ttl)
+        return {1, current}
+        "#,
+    )
+});
+
+// Subtracts `dec` from a counter without going below zero, keeping its expiry.
+static QUOTA_REFUND: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        -- quota_refund
+        local dec = tonumber(ARGV[1])
+        local current = tonumber(redis.call('GET', KEYS[1]) or '0')
+        if current <= 0 then
+            return 0
+        end
+        local remaining = math.max(0, current - dec)
+        redis.call('SET', KEYS[1], remaining, 'KEEPTTL')
+        return remaining
+        "#,
+    )
+});
+
+// Without Redis the limits are kept per process instead of across replicas.
+static LOCAL_BUCKETS: LazyLock<Mutex<HashMap<Uuid, TokenBucket>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static LOCAL_QUOTAS: LazyLock<Mutex<HashMap<Uuid, (NaiveDate, i64)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    ts: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quota {
+    Allowed,
+    Limited { retry_after_secs: u64 },
+}
+
+/// Generates a new API key. Only its hash is stored, so it is shown once at creation.
+pub fn generate_key() -> String {
+    format!("wtk_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub fn hash_key(key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+/// Takes one request token from the key's per-minute bucket. The bucket lives in Redis so the
+/// limit holds across replicas.
+pub async fn take_request(state: &AppState, key: &ApiKeyRecord) -> anyhow::Result<Quota> {
+    let capacity = f64::from(key.requests_per_minute);
+    let refill_per_ms = capacity / 60_000.0;
+    let now_ms = Utc::now().timestamp_millis();
+    let (allowed, wait_ms) = match state.redis.clone() {
+        Some(mut redis) => {
+            TOKEN_BUCKET
+                .key(format!("watchtower:ratelimit:{}", key.id))
+                .arg(capacity)
+                .arg(refill_per_ms)
+                .arg(now_ms)
+                .arg(1)
+                .invoke_async::<(i64, i64)>(&mut redis)
+                .await?
+        }
+        None => {
+            let mut buckets = LOCAL_BUCKETS.lock().unwrap_or_else(|err| err.into_inner());
+            let bucket = buckets.entry(key.id).or_insert(TokenBucket {
+                tokens: capacity,
+                ts: now_ms,
+            });
+            take_token(bucket, capacity, refill_per_ms, now_ms)
+        }
+    };
+
+    if allowed == 1 {
+        return Ok(Quota::Allowed);
+    }
+    Ok(Quota::Limited {
+        retry_after_secs: u64::try_from(wait_ms).unwrap_or(0).div_ceil(1000).max(1),
+    })
+}
+
+/// Counts `count` submitted transactions against the key's UTC-day quota.
+pub async fn take_daily_txs(
+    state: &AppState,
+    key: &ApiKeyRecord,
+    count: usize,
+) -> anyhow::Result<Quota> {
+    let now = Utc::now();
+    let allowed = match state.redis.clone() {
+        Some(mut redis) => {
+            let (allowed, _current): (i64, i64) = QUOTA_COUNTER
+                .key(daily_quota_key(key, now))
+                .arg(key.txs_per_day)
+                .arg(count)
+                .arg(DAILY_QUOTA_TTL_SECONDS)
+                .invoke_async(&mut redis)
+                .await?;
+            allowed == 1
+        }
+        None => {
+            let mut quotas = LOCAL_QUOTAS.lock().unwrap_or_else(|err| err.into_inner());
+            let (day, used) = quotas.entry(key.id).or_insert((now.date_naive(), 0));
+            if *day != now.date_naive() {
+                *day = now.date_naive();
+                *used = 0;
+            }
+            let requested = i64::try_from(count).unwrap_or(i64::MAX);
+            let allowed = used.saturating_add(requested) <= i64::from(key.txs_per_day);
+            if allowed {
+                *used += requested;
+            }
+            allowed
+        }
+    };
+
+    if allowed {
+        return Ok(Quota::Allowed);
+    }
+    Ok(Quota::Limited {
+        retry_after_secs: seconds_until_next_day(now),
+    })
+}
+
+/// Gives back `count` transactions taken by [`take_daily_txs`] for a submission that was not
+/// stored.
+pub async fn refund_daily_txs(
+    state: &AppState,
+    key: &ApiKeyRecord,
+    count: usize,
+) -> anyhow::Result<()> {
+    let now = Utc::now();
+    match state.redis.clone() {
+        Some(mut redis) => {
+            let _: i64 = QUOTA_REFUND
+                .key(daily_quota_key(key, now))
+                .arg(count)
+                .invoke_async(&mut redis)
+                .await?;
+        }
+        None => {
+            let mut quotas = LOCAL_QUOTAS.lock().unwrap_or_else(|err| err.into_inner());
+            if let Some((day, used)) = quotas.get_mut(&key.id)
+                && *day == now.date_naive()
+            {
+                let refunded = i64::try_from(count).unwrap_or(i64::MAX);
+                *used = used.saturating_sub(refunded).max(0);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn daily_quota_key(key: &ApiKeyRecord, now: DateTime<Utc>) -> String {
+    format!("watchtower:quota:txs:{}:{}", key.id, now.format("%Y%m%d"))
+}
+
+/// In-process version of the `token_bucket` script.
+fn take_token(
+    bucket: &mut TokenBucket,
+    capacity: f64,
+    refill_per_ms: f64,
+    now_ms: i64,
+) -> (i64, i64) {
+    let elapsed = (now_ms - bucket.ts).max(0) as f64;
+    bucket.tokens = capacity.min(bucket.tokens + elapsed * refill_per_ms);
+    bucket.ts = now_ms;
+    if bucket.tokens >= 1.0 {
+        bucket.tokens -= 1.0;
+        return (1, 0);
+    }
+    (0, ((1.0 - bucket.tokens) / refill_per_ms).ceil() as i64)
+}
+
+fn seconds_until_next_day(now: DateTime<Utc>) -> u64 {
+    let next_day = (now.date_naive() + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc();
+    u64::try_from((next_day - now).num_seconds())
+        .unwrap_or(0)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{TokenBucket, generate_key, hash_key, seconds_until_next_day, take_token};
+
+    #[test]
+    fn generated_keys_hash_deterministically() {
+        let key = generate_key();
+        assert!(key.starts_with("wtk_"));
+        assert_eq!(key.len(), "wtk_".len() + 64);
+        assert_eq!(hash_key(&key), hash_key(&key));
+        assert_ne!(hash_key(&key), hash_key(&generate_key()));
+    }
+
+    #[test]
+    fn daily_quota_resets_at_utc_midnight() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 23, 59, 30).unwrap();
+        assert_eq!(seconds_until_next_day(now), 30);
+    }
+
+    #[test]
+    fn local_bucket_refills_per_minute() {
+        let capacity = 2.0;
+        let refill_per_ms = capacity / 60_000.0;
+        let mut bucket = TokenBucket {
+            tokens: capacity,
+            ts: 0,
+        };
+
+        assert_eq!(take_token(&mut bucket, capacity, refill_per_ms, 0), (1, 0));
+        assert_eq!(take_token(&mut bucket, capacity, refill_per_ms, 0), (1, 0));
+        assert_eq!(
+            take_token(&mut bucket, capacity, refill_per_ms, 0),
+            (0, 30_000)
+        );
+        assert_eq!(
+            take_token(&mut bucket, capacity, refill_per_ms, 30_000),
+            (1, 0)
+        );
+    }
+}
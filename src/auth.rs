@@ -0,0 +1,618 @@
+use alloy::primitives::{Address, B256, FixedBytes, U256, keccak256};
+use alloy::sol;
+use alloy::sol_types::{Eip712Domain, SolStruct, eip712_domain};
+use axum::http::HeaderMap;
+use chrono::{TimeZone, Utc};
+use tempo_alloy::contracts::precompiles::IAccountKeychain::KeyInfo;
+use tempo_alloy::primitives::TempoSignature;
+
+use crate::db;
+use crate::rpc::fetch_access_key;
+use crate::state::AppState;
+
+pub const AUTHORIZATION_HEADER: &str = "authorization";
+pub const AUTH_EXPIRY_HEADER: &str = "x-auth-expiry";
+pub const AUTH_NONCE_HEADER: &str = "x-auth-nonce";
+
+const DOMAIN_NAME: &str = "Temprano Watchtower";
+const DOMAIN_VERSION: &str = "1";
+
+sol! {
+    /// Typed-data message authorizing a local group cancel.
+    #[derive(Debug)]
+    struct CancelGroup {
+        address sender;
+        bytes16 groupId;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing a local cancel of the group's nonces in
+    /// `fromNonce..=toNonce`.
+    #[derive(Debug)]
+    struct CancelGroupNonces {
+        address sender;
+        bytes16 groupId;
+        uint64 fromNonce;
+        uint64 toNonce;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing a local cancel of one transaction.
+    #[derive(Debug)]
+    struct CancelTransaction {
+        address sender;
+        bytes32 txHash;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing a webhook for every transaction of the sender.
+    #[derive(Debug)]
+    struct RegisterSenderWebhook {
+        address sender;
+        string url;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing a webhook for one of the sender's groups.
+    #[derive(Debug)]
+    struct RegisterGroupWebhook {
+        address sender;
+        bytes16 groupId;
+        string url;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing a read of one of the sender's webhooks.
+    #[derive(Debug)]
+    struct GetWebhook {
+        address sender;
+        bytes16 webhookId;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing the deletion of one of the sender's webhooks.
+    #[derive(Debug)]
+    struct DeleteWebhook {
+        address sender;
+        bytes16 webhookId;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing a group pause.
+    #[derive(Debug)]
+    struct PauseGroup {
+        address sender;
+        bytes16 groupId;
+        uint64 expiry;
+        uint256 nonce;
+    }
+
+    /// Typed-data message authorizing a paused group to resume.
+    #[derive(Debug)]
+    struct ResumeGroup {
+        address sender;
+        bytes16 groupId;
+        uint64 expiry;
+        uint256 nonce;
+    }
+}
+
+/// Why a request was not authorized. The API maps these onto its error responses.
+#[derive(Debug)]
+pub enum AuthError {
+    BadRequest(&'static str),
+    Unauthorized(&'static str),
+    Internal(anyhow::Error),
+}
+
+/// The action a sender authorizes.
+#[derive(Debug, Clone, Copy)]
+pub enum SignedAction<'a> {
+    CancelGroup(&'a [u8]),
+    CancelGroupNonces {
+        group_id: &'a [u8],
+        from_nonce: u64,
+        to_nonce: u64,
+    },
+    CancelTransaction(&'a [u8]),
+    PauseGroup(&'a [u8]),
+    ResumeGroup(&'a [u8]),
+    RegisterWebhook {
+        group_id: Option<&'a [u8]>,
+        url: &'a str,
+    },
+    GetWebhook(&'a [u8]),
+    DeleteWebhook(&'a [u8]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Authorization {
+    /// `Authorization: EIP712 <hex>` with `x-auth-expiry` and `x-auth-nonce`.
+    Typed {
+        signature: Vec<u8>,
+        expiry: u64,
+        nonce: U256,
+    },
+    /// `Authorization: Signature <hex>` over `keccak256(target)`. Replayable, so only accepted
+    /// with `api.legacy_cancel_signatures`.
+    Legacy { signature: Vec<u8> },
+}
+
+impl Authorization {
+    pub fn signature(&self) -> &[u8] {
+        match self {
+            Self::Typed { signature, .. } | Self::Legacy { signature } => signature,
+        }
+    }
+}
+
+pub fn domain(chain_id: u64) -> Eip712Domain {
+    eip712_domain! {
+        name: DOMAIN_NAME,
+        version: DOMAIN_VERSION,
+        chain_id: chain_id,
+    }
+}
+
+/// Checks the sender's authorization for `target` and uses up its nonce. EIP-712
+/// authorizations name the chain they act on, so `chain_id` is required for them.
+pub async fn authorize(
+    state: &AppState,
+    headers: &HeaderMap,
+    sender_bytes: &[u8],
+    chain_id: Option<u64>,
+    target: SignedAction<'_>,
+) -> Result<(), AuthError> {
+    let authorization = parse_headers(headers).map_err(AuthError::Unauthorized)?;
+    let config = state.config.current();
+    let expires_at = match &authorization {
+        Authorization::Typed { expiry, .. } => {
+            let now = Utc::now().timestamp().max(0) as u64;
+            if *expiry <= now {
+                return Err(AuthError::Unauthorized("authorization expired"));
+            }
+            if *expiry > now.saturating_add(config.api.cancel_auth_max_ttl_seconds) {
+                return Err(AuthError::Unauthorized(
+                    "authorization expiry is too far in the future",
+                ));
+            }
+            Some(
+                i64::try_from(*expiry)
+                    .ok()
+                    .and_then(|expiry| Utc.timestamp_opt(expiry, 0).single())
+                    .ok_or(AuthError::Unauthorized("invalid x-auth-expiry header"))?,
+            )
+        }
+        Authorization::Legacy { .. } if !config.api.legacy_cancel_signatures => {
+            return Err(AuthError::Unauthorized(
+                "legacy signatures are disabled, sign an EIP-712 cancel message",
+            ));
+        }
+        Authorization::Legacy { .. } => None,
+    };
+    if expires_at.is_some() && chain_id.is_none() {
+        return Err(AuthError::BadRequest("chainId is required"));
+    }
+
+    let sender =
+        Address::try_from(sender_bytes).map_err(|_| AuthError::BadRequest("invalid sender"))?;
+    let hash = signing_hash(&authorization, chain_id.unwrap_or_default(), sender, target)
+        .map_err(AuthError::BadRequest)?;
+    verify_signature(state, chain_id, sender, &hash, authorization.signature()).await?;
+
+    if let (Authorization::Typed { nonce, .. }, Some(expires_at)) = (&authorization, expires_at) {
+        let fresh = db::use_auth_nonce(
+            &state.db,
+            sender_bytes,
+            &nonce.to_be_bytes::<32>(),
+            expires_at,
+        )
+        .await
+        .map_err(AuthError::Internal)?;
+        if !fresh {
+            return Err(AuthError::Unauthorized("authorization nonce already used"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `signature` over `hash` was made by `sender`, with any Tempo signature type. A
+/// keychain signature made with one of the sender's access keys counts as the sender's once the
+/// key is confirmed active in the account keychain on `chain_id`.
+pub async fn verify_signature(
+    state: &AppState,
+    chain_id: Option<u64>,
+    sender: Address,
+    hash: &B256,
+    signature: &[u8],
+) -> Result<(), AuthError> {
+    let signature = TempoSignature::from_bytes(signature)
+        .map_err(|_| AuthError::Unauthorized("invalid signature"))?;
+    let keychain = match &signature {
+        TempoSignature::Primitive(_) => None,
+        TempoSignature::Keychain(keychain) => Some(keychain),
+    };
+    let recovered = signature
+        .recover_signer(hash)
+        .map_err(|_| AuthError::Unauthorized("invalid signature"))?;
+    if recovered != sender {
+        return Err(AuthError::Unauthorized("signature does not match sender"));
+    }
+    let Some(keychain) = keychain else {
+        return Ok(());
+    };
+
+    let key_id = keychain
+        .key_id(hash)
+        .map_err(|_| AuthError::Unauthorized("invalid signature"))?;
+    let chain_id = chain_id.ok_or(AuthError::BadRequest(
+        "chainId is required for access key signatures",
+    ))?;
+    let chain = state
+        .rpcs
+        .chain(chain_id)
+        .ok_or(AuthError::BadRequest("unsupported chainId"))?;
+    let key = fetch_access_key(&chain, sender, key_id)
+        .await
+        .map_err(AuthError::Internal)?;
+    check_access_key(&key, key_id, Utc::now().timestamp().max(0) as u64)
+        .map_err(AuthError::Unauthorized)
+}
+
+/// An access key may act for its account while it is registered, not revoked and not expired.
+/// An `expiry` of zero never expires.
+fn check_access_key(key: &KeyInfo, key_id: Address, now: u64) -> Result<(), &'static str> {
+    if key_id.is_zero() || key.keyId != key_id {
+        return Err("access key is not authorized for sender");
+    }
+    if key.isRevoked {
+        return Err("access key is revoked");
+    }
+    if key.expiry != 0 && key.expiry <= now {
+        return Err("access key expired");
+    }
+    Ok(())
+}
+
+pub fn parse_headers(headers: &HeaderMap) -> Result<Authorization, &'static str> {
+    let value = headers
+        .get(AUTHORIZATION_HEADER)
+        .ok_or("missing authorization header")?
+        .to_str()
+        .map_err(|_| "invalid authorization header")?;
+    let mut parts = value.split_whitespace();
+    let (Some(scheme), Some(signature), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("invalid authorization header");
+    };
+    let signature = decode_hex(signature).ok_or("invalid signature")?;
+
+    match scheme {
+        "EIP712" => {
+            let expiry = header_str(headers, AUTH_EXPIRY_HEADER)?
+                .parse::<u64>()
+                .map_err(|_| "invalid x-auth-expiry header")?;
+            let nonce = header_str(headers, AUTH_NONCE_HEADER)?;
+            let nonce = nonce
+                .parse::<U256>()
+                .map_err(|_| "invalid x-auth-nonce header")?;
+            Ok(Authorization::Typed {
+                signature,
+                expiry,
+                nonce,
+            })
+        }
+        "Signature" => Ok(Authorization::Legacy { signature }),
+        _ => Err("invalid authorization header"),
+    }
+}
+
+/// The hash the sender signed. Typed authorizations are bound to `chain_id`; legacy ones are not
+/// and only ever authorize cancels.
+pub fn signing_hash(
+    authorization: &Authorization,
+    chain_id: u64,
+    sender: Address,
+    target: SignedAction<'_>,
+) -> Result<B256, &'static str> {
+    let Authorization::Typed { expiry, nonce, .. } = authorization else {
+        return Ok(match target {
+            SignedAction::CancelGroup(group_id) => keccak256(group_id),
+            SignedAction::CancelTransaction(tx_hash) => keccak256(tx_hash),
+            SignedAction::CancelGroupNonces { .. } => {
+                return Err("legacy signatures cannot cancel a nonce range");
+            }
+            SignedAction::PauseGroup(_)
+            | SignedAction::ResumeGroup(_)
+            | SignedAction::RegisterWebhook { .. }
+            | SignedAction::GetWebhook(_)
+            | SignedAction::DeleteWebhook(_) => {
+                return Err("legacy signatures only authorize cancels");
+            }
+        });
+    };
+
+    let domain = domain(chain_id);
+    let hash = match target {
+        SignedAction::CancelGroup(group_id) => CancelGroup {
+            sender,
+            groupId: FixedBytes::try_from(group_id).map_err(|_| "invalid group id")?,
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::CancelGroupNonces {
+            group_id,
+            from_nonce,
+            to_nonce,
+        } => CancelGroupNonces {
+            sender,
+            groupId: FixedBytes::try_from(group_id).map_err(|_| "invalid group id")?,
+            fromNonce: from_nonce,
+            toNonce: to_nonce,
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::CancelTransaction(tx_hash) => CancelTransaction {
+            sender,
+            txHash: FixedBytes::try_from(tx_hash).map_err(|_| "invalid tx hash")?,
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::PauseGroup(group_id) => PauseGroup {
+            sender,
+            groupId: FixedBytes::try_from(group_id).map_err(|_| "invalid group id")?,
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::ResumeGroup(group_id) => ResumeGroup {
+            sender,
+            groupId: FixedBytes::try_from(group_id).map_err(|_| "invalid group id")?,
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::RegisterWebhook {
+            group_id: None,
+            url,
+        } => RegisterSenderWebhook {
+            sender,
+            url: url.to_string(),
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::RegisterWebhook {
+            group_id: Some(group_id),
+            url,
+        } => RegisterGroupWebhook {
+            sender,
+            groupId: FixedBytes::try_from(group_id).map_err(|_| "invalid group id")?,
+            url: url.to_string(),
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::GetWebhook(webhook_id) => GetWebhook {
+            sender,
+            webhookId: FixedBytes::try_from(webhook_id).map_err(|_| "invalid webhook id")?,
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+        SignedAction::DeleteWebhook(webhook_id) => DeleteWebhook {
+            sender,
+            webhookId: FixedBytes::try_from(webhook_id).map_err(|_| "invalid webhook id")?,
+            expiry: *expiry,
+            nonce: *nonce,
+        }
+        .eip712_signing_hash(&domain),
+    };
+    Ok(hash)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &'static str) -> Result<&'a str, &'static str> {
+    let value = headers.get(name).ok_or(match name {
+        AUTH_EXPIRY_HEADER => "missing x-auth-expiry header",
+        _ => "missing x-auth-nonce header",
+    })?;
+    value.to_str().map_err(|_| "invalid authorization header")
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, U256, keccak256};
+    use axum::http::{HeaderMap, HeaderValue};
+    use tempo_alloy::contracts::precompiles::IAccountKeychain::KeyInfo;
+
+    use super::{Authorization, SignedAction, check_access_key, parse_headers, signing_hash};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn parses_typed_and_legacy_headers() {
+        let typed = parse_headers(&headers(&[
+            ("authorization", "EIP712 0xabcd"),
+            ("x-auth-expiry", "1735689600"),
+            ("x-auth-nonce", "0x2a"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            typed,
+            Authorization::Typed {
+                signature: vec![0xab, 0xcd],
+                expiry: 1_735_689_600,
+                nonce: U256::from(42),
+            }
+        );
+
+        let legacy = parse_headers(&headers(&[("authorization", "Signature abcd")])).unwrap();
+        assert_eq!(
+            legacy,
+            Authorization::Legacy {
+                signature: vec![0xab, 0xcd]
+            }
+        );
+
+        assert_eq!(
+            parse_headers(&headers(&[("authorization", "EIP712 0xabcd")])),
+            Err("missing x-auth-expiry header")
+        );
+        assert_eq!(
+            parse_headers(&headers(&[("authorization", "Bearer 0xabcd")])),
+            Err("invalid authorization header")
+        );
+    }
+
+    #[test]
+    fn access_keys_must_be_active() {
+        let key_id = Address::repeat_byte(0x44);
+        let key = KeyInfo {
+            signatureType: 1,
+            keyId: key_id,
+            expiry: 2_000,
+            enforceLimits: false,
+            isRevoked: false,
+        };
+
+        assert_eq!(check_access_key(&key, key_id, 1_000), Ok(()));
+        assert_eq!(
+            check_access_key(&key, key_id, 2_000),
+            Err("access key expired")
+        );
+        assert_eq!(
+            check_access_key(
+                &KeyInfo {
+                    expiry: 0,
+                    ..key.clone()
+                },
+                key_id,
+                5_000
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_access_key(
+                &KeyInfo {
+                    isRevoked: true,
+                    ..key.clone()
+                },
+                key_id,
+                1_000
+            ),
+            Err("access key is revoked")
+        );
+        assert_eq!(
+            check_access_key(&key, Address::repeat_byte(0x55), 1_000),
+            Err("access key is not authorized for sender")
+        );
+        assert_eq!(
+            check_access_key(
+                &KeyInfo {
+                    keyId: Address::ZERO,
+                    ..key
+                },
+                Address::ZERO,
+                1_000
+            ),
+            Err("access key is not authorized for sender")
+        );
+    }
+
+    #[test]
+    fn typed_hash_is_bound_to_chain_target_and_nonce() {
+        let sender = Address::repeat_byte(0x11);
+        let group_id = [0x22; 16];
+        let typed = |nonce: u64| Authorization::Typed {
+            signature: Vec::new(),
+            expiry: 1_735_689_600,
+            nonce: U256::from(nonce),
+        };
+        let hash = |auth: &Authorization, chain_id, target| {
+            signing_hash(auth, chain_id, sender, target).unwrap()
+        };
+
+        let base = hash(&typed(1), 42431, SignedAction::CancelGroup(&group_id));
+        assert_ne!(
+            base,
+            hash(&typed(1), 1, SignedAction::CancelGroup(&group_id))
+        );
+        assert_ne!(
+            base,
+            hash(&typed(2), 42431, SignedAction::CancelGroup(&group_id))
+        );
+        assert_ne!(
+            base,
+            hash(&typed(1), 42431, SignedAction::CancelGroup(&[0x33; 16]))
+        );
+        let pause = hash(&typed(1), 42431, SignedAction::PauseGroup(&group_id));
+        let resume = hash(&typed(1), 42431, SignedAction::ResumeGroup(&group_id));
+        assert_ne!(base, pause);
+        assert_ne!(base, resume);
+        assert_ne!(pause, resume);
+
+        let webhook = |group_id, url| SignedAction::RegisterWebhook { group_id, url };
+        let sender_hook = hash(&typed(1), 42431, webhook(None, "https://a.example/"));
+        assert_ne!(
+            sender_hook,
+            hash(&typed(1), 42431, webhook(None, "https://b.example/"))
+        );
+        assert_ne!(
+            sender_hook,
+            hash(
+                &typed(1),
+                42431,
+                webhook(Some(&group_id[..]), "https://a.example/")
+            )
+        );
+
+        let range = |from_nonce, to_nonce| SignedAction::CancelGroupNonces {
+            group_id: &group_id,
+            from_nonce,
+            to_nonce,
+        };
+        let from_two = hash(&typed(1), 42431, range(2, u64::MAX));
+        assert_ne!(base, from_two);
+        assert_ne!(from_two, hash(&typed(1), 42431, range(3, u64::MAX)));
+        assert_ne!(from_two, hash(&typed(1), 42431, range(2, 5)));
+
+        let legacy = Authorization::Legacy {
+            signature: Vec::new(),
+        };
+        assert_eq!(
+            hash(&legacy, 42431, SignedAction::CancelGroup(&group_id)),
+            keccak256(group_id)
+        );
+        assert_eq!(
+            hash(&legacy, 1, SignedAction::CancelGroup(&group_id)),
+            keccak256(group_id)
+        );
+        assert_eq!(
+            signing_hash(&legacy, 42431, sender, SignedAction::PauseGroup(&group_id)),
+            Err("legacy signatures only authorize cancels")
+        );
+        assert_eq!(
+            signing_hash(&legacy, 42431, sender, range(2, u64::MAX)),
+            Err("legacy signatures cannot cancel a nonce range")
+        );
+    }
+}
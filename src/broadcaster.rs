@@ -1,8 +1,13 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use alloy::providers::Provider;
+use sqlx::PgPool;
+use tracing::warn;
 
+use crate::db;
+use crate::models::{BroadcastAttemptOutcome, NewBroadcastAttempt};
 use crate::rpc::ChainRpc;
+use crate::telemetry;
 
 #[derive(Debug)]
 pub enum BroadcastOutcome {
@@ -12,6 +17,8 @@ pub enum BroadcastOutcome {
 }
 
 pub async fn broadcast_raw_tx(
+    pool: &PgPool,
+    tx_id: i64,
     chain: &ChainRpc,
     raw_tx: &[u8],
     fanout: usize,
@@ -24,36 +31,70 @@ pub async fn broadcast_raw_tx(
         };
     }
 
-    let total = chain.http.len();
-    let fanout = fanout.max(1).min(total);
-    let start = (attempt.max(0) as usize) % total;
+    let fanout = fanout.max(1).min(chain.http.len());
 
     let mut errors = Vec::new();
     let mut invalid_errors = Vec::new();
     let mut accepted = false;
+    let mut history = Vec::with_capacity(fanout);
 
-    for idx in 0..fanout {
-        let provider = chain.http[(start + idx) % total].clone();
+    for endpoint in chain.ranked_endpoints().into_iter().take(fanout) {
+        let provider = chain.http[endpoint].clone();
+        let label = chain.endpoint_label(endpoint);
+        let started = Instant::now();
         let res = tokio::time::timeout(timeout, provider.send_raw_transaction(raw_tx)).await;
-        match res {
+        let latency = started.elapsed();
+        let (outcome, error) = match res {
             Ok(Ok(_pending)) => {
                 accepted = true;
+                (BroadcastAttemptOutcome::Accepted, None)
             }
             Ok(Err(err)) => {
-                let msg = err.to_string();
+                // Transport errors can quote the request URL.
+                let msg = err.to_string().replace(&chain.urls[endpoint], &label);
                 match classify_error(&msg) {
                     ErrorClass::AlreadyKnown => {
                         accepted = true;
-                        errors.push(msg);
+                        errors.push(msg.clone());
+                        (BroadcastAttemptOutcome::AlreadyKnown, Some(msg))
+                    }
+                    ErrorClass::Invalid => {
+                        invalid_errors.push(msg.clone());
+                        (BroadcastAttemptOutcome::Invalid, Some(msg))
+                    }
+                    ErrorClass::Retry => {
+                        errors.push(msg.clone());
+                        (BroadcastAttemptOutcome::Retry, Some(msg))
                     }
-                    ErrorClass::Invalid => invalid_errors.push(msg),
-                    ErrorClass::Retry => errors.push(msg),
                 }
             }
             Err(_elapsed) => {
                 errors.push("broadcast timeout".to_string());
+                (
+                    BroadcastAttemptOutcome::Timeout,
+                    Some("broadcast timeout".to_string()),
+                )
             }
-        }
+        };
+        // Invalid-tx rejections are answered by a working node, so they don't count against it.
+        let endpoint_ok = !matches!(
+            outcome,
+            BroadcastAttemptOutcome::Retry | BroadcastAttemptOutcome::Timeout
+        );
+        chain.record_result(endpoint, endpoint_ok, latency);
+        telemetry::record_rpc_request(chain.chain_id, &label, latency, !endpoint_ok);
+        history.push(NewBroadcastAttempt {
+            tx_id,
+            attempt: attempt.saturating_add(1),
+            endpoint: label,
+            outcome,
+            error,
+            latency_ms: latency.as_millis() as i64,
+        });
+    }
+
+    if let Err(err) = db::insert_broadcast_attempts(pool, &history).await {
+        warn!(tx_id, error = %err, "failed to record broadcast attempts");
     }
 
     if accepted {
@@ -1,31 +1,72 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::{Arc, PoisonError, RwLock},
+};
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
+/// Shared view of the running configuration. Readers take a snapshot with [`current`] and keep
+/// it for the duration of one unit of work, so a reload never changes settings mid-operation.
+///
+/// [`current`]: ConfigHandle::current
+#[derive(Clone, Debug)]
+pub struct ConfigHandle {
+    inner: Arc<RwLock<Arc<Config>>>,
+}
+
+impl ConfigHandle {
+    pub fn new(config: Config) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Arc::new(config))),
+        }
+    }
+
+    pub fn current(&self) -> Arc<Config> {
+        self.inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn replace(&self, config: Config) {
+        *self.inner.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(config);
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub database: DatabaseConfig,
-    pub redis: RedisConfig,
+    /// Without Redis the scheduler works from Postgres alone and wakes up on `LISTEN/NOTIFY`.
+    pub redis: Option<RedisConfig>,
     pub rpc: RpcConfig,
     pub scheduler: SchedulerConfig,
     pub broadcaster: BroadcasterConfig,
     pub watcher: WatcherConfig,
     pub api: ApiConfig,
+    pub webhooks: WebhookConfig,
+    pub retention: RetentionConfig,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct ServerConfig {
     pub bind: String,
+    #[serde(default = "default_shutdown_timeout_ms")]
+    pub shutdown_timeout_ms: u64,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+fn default_shutdown_timeout_ms() -> u64 {
+    20_000
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct DatabaseConfig {
     pub url: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct RedisConfig {
     pub url: String,
 }
@@ -33,6 +74,23 @@ pub struct RedisConfig {
 #[derive(Clone, Debug, Deserialize)]
 pub struct RpcConfig {
     pub chains: HashMap<u64, Vec<String>>,
+    pub health: RpcHealthConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct RpcHealthConfig {
+    pub failure_threshold: u32,
+    pub cooldown_ms: u64,
+}
+
+impl Default for RpcHealthConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown_ms: 30_000,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -44,6 +102,34 @@ pub struct SchedulerConfig {
     pub retry_max_ms: u64,
     pub expiry_soon_window_seconds: i64,
     pub expiry_soon_retry_max_ms: u64,
+    /// Default for fee ladders that don't set `escalateAfterAttempts`.
+    #[serde(default = "default_escalate_after_attempts")]
+    pub escalate_after_attempts: i32,
+    /// Default for fee ladders that don't set `escalateAfterSeconds`.
+    #[serde(default = "default_escalate_after_seconds")]
+    pub escalate_after_seconds: i64,
+    /// How many nonces past the lowest pending one may be broadcast for the same sender and nonce
+    /// key. Zero broadcasts strictly in nonce order.
+    #[serde(default)]
+    pub nonce_lookahead: u32,
+    /// How often the Redis schedule is compared with Postgres and repaired. Zero disables it.
+    #[serde(default = "default_redis_reconcile_interval_ms")]
+    pub redis_reconcile_interval_ms: u64,
+    /// Replace the Redis schedule with the database's at startup instead of only repairing it.
+    #[serde(default)]
+    pub rebuild_redis_on_start: bool,
+}
+
+fn default_redis_reconcile_interval_ms() -> u64 {
+    60_000
+}
+
+fn default_escalate_after_attempts() -> i32 {
+    3
+}
+
+fn default_escalate_after_seconds() -> i64 {
+    60
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -56,28 +142,158 @@ pub struct BroadcasterConfig {
 pub struct WatcherConfig {
     pub poll_interval_ms: u64,
     pub use_websocket: bool,
+    /// How often every active transaction's receipt and nonce are checked individually, on top
+    /// of matching new blocks.
+    #[serde(default = "default_reconcile_interval_ms")]
+    pub reconcile_interval_ms: u64,
+    /// Depth, counting the receipt's own block, at which an executed transaction is confirmed.
+    /// Until then its block hash is checked against the canonical chain on every tick.
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u64,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+fn default_reconcile_interval_ms() -> u64 {
+    30_000
+}
+
+fn default_confirmations() -> u64 {
+    6
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct ApiConfig {
     pub max_body_bytes: usize,
+    /// Rejects requests without a valid `x-api-key`. Keys are always enforced when sent.
+    #[serde(default)]
+    pub require_api_key: bool,
+    /// Minimum `max_fee_per_gas` increase, in percent, for a submission to replace a pending
+    /// transaction with the same sender, nonce key and nonce.
+    #[serde(default = "default_replacement_bump_percent")]
+    pub replacement_bump_percent: u64,
+    /// Accepts `Authorization: Signature` cancels over a bare `keccak256` hash. They can be
+    /// replayed and are not bound to a chain; only meant for migrating to EIP-712 signatures.
+    #[serde(default)]
+    pub legacy_cancel_signatures: bool,
+    /// Furthest in the future an EIP-712 cancel authorization may expire.
+    #[serde(default = "default_cancel_auth_max_ttl_seconds")]
+    pub cancel_auth_max_ttl_seconds: u64,
+}
+
+fn default_replacement_bump_percent() -> u64 {
+    10
+}
+
+fn default_cancel_auth_max_ttl_seconds() -> u64 {
+    3600
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub poll_interval_ms: u64,
+    pub timeout_ms: u64,
+    pub batch_size: i64,
+    pub lease_ttl_seconds: i64,
+    pub max_attempts: i32,
+    pub retry_min_ms: u64,
+    pub retry_max_ms: u64,
+    /// Hosts webhooks may target even though they resolve to loopback, private or link-local
+    /// addresses. Every other host must resolve to public addresses only. Unlike the rest of the
+    /// section, this list is reloaded without a restart.
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 500,
+            timeout_ms: 5000,
+            batch_size: 50,
+            lease_ttl_seconds: 30,
+            max_attempts: 12,
+            retry_min_ms: 1000,
+            retry_max_ms: 3_600_000,
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    pub enabled: bool,
+    pub interval_ms: u64,
+    /// Rows handled per statement, so no single pass holds locks for long.
+    pub batch_size: i64,
+    pub archive: ArchiveTarget,
+    /// Directory for `archive = "jsonl"` exports.
+    pub archive_dir: PathBuf,
+    /// Keyed by terminal status.
+    pub policies: BTreeMap<String, RetentionPolicy>,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: 3_600_000,
+            batch_size: 1000,
+            archive: ArchiveTarget::Table,
+            archive_dir: PathBuf::from("archive"),
+            policies: BTreeMap::new(),
+        }
+    }
+}
+
+/// Where archived transactions go before they are deleted from `txs`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveTarget {
+    #[default]
+    Table,
+    Jsonl,
+}
+
+/// Ages are measured from the transaction's last status change.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct RetentionPolicy {
+    pub strip_raw_tx_after_days: Option<u32>,
+    pub archive_after_days: Option<u32>,
+}
+
+/// Statuses a retention policy may be set for. `executed` is left out because it still
+/// becomes `confirmed` or is rescheduled after a reorg. `replaced` rows are only touched once
+/// their nonce is no longer pending.
+pub const RETENTION_STATUSES: [&str; 6] = [
+    "confirmed",
+    "expired",
+    "invalid",
+    "stale_by_nonce",
+    "canceled_locally",
+    "replaced",
+];
+
 #[derive(Debug, Deserialize)]
 struct ConfigRaw {
     server: ServerConfig,
     database: DatabaseConfig,
-    redis: RedisConfig,
+    redis: Option<RedisConfig>,
     rpc: RpcConfigRaw,
     scheduler: SchedulerConfig,
     broadcaster: BroadcasterConfig,
     watcher: WatcherConfig,
     api: ApiConfig,
+    #[serde(default)]
+    webhooks: WebhookConfig,
+    #[serde(default)]
+    retention: RetentionConfig,
 }
 
 #[derive(Debug, Deserialize)]
 struct RpcConfigRaw {
     chains: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    health: RpcHealthConfig,
 }
 
 impl Config {
@@ -95,8 +311,16 @@ impl Config {
             .with_context(|| format!("read config file {}", path.display()))?;
         let expanded = shellexpand::env(&raw)
             .with_context(|| format!("expand env vars in {}", path.display()))?;
-        let parsed: ConfigRaw = toml::from_str(expanded.as_ref())
+        let config = Self::parse(expanded.as_ref())
             .with_context(|| format!("parse config file {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("validate config file {}", path.display()))?;
+        Ok(config)
+    }
+
+    fn parse(raw: &str) -> Result<Self> {
+        let parsed: ConfigRaw = toml::from_str(raw)?;
 
         let mut chains = HashMap::new();
         for (key, urls) in parsed.rpc.chains {
@@ -110,11 +334,165 @@ impl Config {
             server: parsed.server,
             database: parsed.database,
             redis: parsed.redis,
-            rpc: RpcConfig { chains },
+            rpc: RpcConfig {
+                chains,
+                health: parsed.rpc.health,
+            },
             scheduler: parsed.scheduler,
             broadcaster: parsed.broadcaster,
             watcher: parsed.watcher,
             api: parsed.api,
+            webhooks: parsed.webhooks,
+            retention: parsed.retention,
         })
     }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.rpc.chains.is_empty() {
+            anyhow::bail!("rpc.chains must configure at least one chain");
+        }
+        for (chain_id, urls) in &self.rpc.chains {
+            if urls.is_empty() {
+                anyhow::bail!("rpc.chains.{chain_id} must list at least one URL");
+            }
+        }
+        if self.scheduler.poll_interval_ms == 0 {
+            anyhow::bail!("scheduler.poll_interval_ms must be greater than zero");
+        }
+        if self.scheduler.max_concurrency == 0 {
+            anyhow::bail!("scheduler.max_concurrency must be greater than zero");
+        }
+        if self.scheduler.retry_min_ms > self.scheduler.retry_max_ms {
+            anyhow::bail!("scheduler.retry_min_ms must not exceed scheduler.retry_max_ms");
+        }
+        if self.watcher.reconcile_interval_ms == 0 {
+            anyhow::bail!("watcher.reconcile_interval_ms must be greater than zero");
+        }
+        if self.scheduler.escalate_after_attempts <= 0 || self.scheduler.escalate_after_seconds <= 0
+        {
+            anyhow::bail!(
+                "scheduler.escalate_after_attempts and scheduler.escalate_after_seconds must be greater than zero"
+            );
+        }
+        if self.broadcaster.fanout == 0 {
+            anyhow::bail!("broadcaster.fanout must be greater than zero");
+        }
+        if self.watcher.poll_interval_ms == 0 {
+            anyhow::bail!("watcher.poll_interval_ms must be greater than zero");
+        }
+        if self.webhooks.poll_interval_ms == 0 {
+            anyhow::bail!("webhooks.poll_interval_ms must be greater than zero");
+        }
+        if self.retention.interval_ms == 0 || self.retention.batch_size <= 0 {
+            anyhow::bail!(
+                "retention.interval_ms and retention.batch_size must be greater than zero"
+            );
+        }
+        for status in self.retention.policies.keys() {
+            if !RETENTION_STATUSES.contains(&status.as_str()) {
+                anyhow::bail!(
+                    "retention.policies.{status} is not a terminal status; use one of {}",
+                    RETENTION_STATUSES.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArchiveTarget, Config};
+
+    const SAMPLE: &str = r#"
+        [server]
+        bind = "127.0.0.1:8080"
+
+        [database]
+        url = "postgres://localhost/watchtower"
+
+        [redis]
+        url = "redis://localhost:6379/0"
+
+        [rpc.chains]
+        "42431" = ["http://localhost:8545"]
+
+        [scheduler]
+        poll_interval_ms = 200
+        lease_ttl_seconds = 30
+        max_concurrency = 50
+        retry_min_ms = 250
+        retry_max_ms = 5000
+        expiry_soon_window_seconds = 3600
+        expiry_soon_retry_max_ms = 5000
+
+        [broadcaster]
+        fanout = 2
+        timeout_ms = 2000
+
+        [watcher]
+        poll_interval_ms = 1500
+        use_websocket = false
+
+        [api]
+        max_body_bytes = 1048576
+    "#;
+
+    #[test]
+    fn sample_config_is_valid() {
+        let config = Config::parse(SAMPLE).expect("parse");
+        config.validate().expect("valid");
+        assert_eq!(config.rpc.chains[&42431], vec!["http://localhost:8545"]);
+        assert_eq!(config.rpc.health.failure_threshold, 5);
+    }
+
+    #[test]
+    fn validate_rejects_unusable_values() {
+        let zero_concurrency = SAMPLE.replace("max_concurrency = 50", "max_concurrency = 0");
+        let config = Config::parse(&zero_concurrency).expect("parse");
+        assert!(config.validate().is_err());
+
+        let no_urls = SAMPLE.replace(r#"["http://localhost:8545"]"#, "[]");
+        let config = Config::parse(&no_urls).expect("parse");
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn redis_section_is_optional() {
+        let config = Config::parse(SAMPLE).expect("parse");
+        assert!(config.redis.is_some());
+
+        let without_redis = SAMPLE
+            .replace("[redis]", "")
+            .replace(r#"url = "redis://localhost:6379/0""#, "");
+        let config = Config::parse(&without_redis).expect("parse");
+        config.validate().expect("valid");
+        assert!(config.redis.is_none());
+    }
+
+    #[test]
+    fn retention_policies_are_keyed_by_terminal_status() {
+        let with_policy = format!(
+            "{SAMPLE}
+            [retention]
+            enabled = true
+            archive = \"jsonl\"
+
+            [retention.policies.confirmed]
+            strip_raw_tx_after_days = 7
+            archive_after_days = 90
+            "
+        );
+        let config = Config::parse(&with_policy).expect("parse");
+        config.validate().expect("valid");
+        assert_eq!(config.retention.archive, ArchiveTarget::Jsonl);
+        assert_eq!(
+            config.retention.policies["confirmed"].archive_after_days,
+            Some(90)
+        );
+
+        let queued = with_policy.replace("policies.confirmed", "policies.queued");
+        let config = Config::parse(&queued).expect("parse");
+        assert!(config.validate().is_err());
+    }
 }
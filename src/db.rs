@@ -1,9 +1,20 @@
+use std::ops::RangeInclusive;
+
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
-use sqlx_pg_uint::PgU64;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder, Transaction};
+use sqlx_pg_uint::{PgU64, PgU128};
+use uuid::Uuid;
+
+use crate::models::{
+    ApiKeyRecord, BroadcastAttemptRecord, FeeLadderRecord, NewApiKey, NewBroadcastAttempt, NewTx,
+    NewWebhook, ReceiptOutcome, TxEvent, TxRecord, TxStatus, TxStatusCount, WebhookDeliveryJob,
+    WebhookDeliveryStatus, WebhookRecord,
+};
 
-use crate::models::{NewTx, TxRecord, TxStatus};
+/// Channel notified with the chain id whenever a row becomes `queued` or `retry_scheduled`.
+/// See migration `0011_tx_notify.sql`.
+pub const TX_NOTIFY_CHANNEL: &str = "watchtower_txs";
 
 pub async fn connect(url: &str) -> Result<PgPool> {
     Ok(PgPool::connect(url).await?)
@@ -23,11 +34,11 @@ pub async fn insert_tx(
         INSERT INTO txs (
             chain_id, tx_hash, raw_tx, sender, fee_payer, nonce_key, nonce,
             valid_after, valid_before, eligible_at, expires_at, status,
-            group_id, next_action_at
+            group_id, next_action_at, max_fee_per_gas, ladder_id, ladder_rung
         ) VALUES (
             $1, $2, $3, $4, $5, $6, $7,
             $8, $9, $10, $11, $12,
-            $13, $14
+            $13, $14, $15, $16, $17
         )
         ON CONFLICT (chain_id, tx_hash) DO NOTHING
         "#,
@@ -46,6 +57,9 @@ pub async fn insert_tx(
     .bind(&new_tx.status)
     .bind(&new_tx.group_id)
     .bind(new_tx.next_action_at)
+    .bind(&new_tx.max_fee_per_gas)
+    .bind(new_tx.ladder_id)
+    .bind(new_tx.ladder_rung)
     .execute(tx.as_mut())
     .await?;
 
@@ -57,9 +71,330 @@ pub async fn insert_tx(
             .fetch_one(tx.as_mut())
             .await?;
 
+    if !already_known && !new_tx.call_targets.is_empty() {
+        sqlx::query(
+            r#"
+            INSERT INTO tx_call_targets (tx_id, target)
+            SELECT $1, UNNEST($2::BYTEA[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(record.id)
+        .bind(&new_tx.call_targets)
+        .execute(tx.as_mut())
+        .await?;
+    }
+
     Ok((record, already_known))
 }
 
+/// Locks the pending transaction, if any, that `new_tx` would replace: same sender, nonce key
+/// and nonce, but a different hash. A `new_tx` that is already stored replaces nothing.
+pub async fn find_replaceable_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    new_tx: &NewTx,
+) -> Result<Option<TxRecord>> {
+    let record = sqlx::query_as::<_, TxRecord>(
+        r#"
+        SELECT *
+        FROM txs
+        WHERE chain_id = $1
+          AND sender = $2
+          AND nonce_key = $3
+          AND nonce = $4
+          AND tx_hash != $5
+          AND status IN ($6, $7, $8, $9)
+          AND NOT EXISTS (SELECT 1 FROM txs WHERE chain_id = $1 AND tx_hash = $5)
+        ORDER BY id DESC
+        LIMIT 1
+        FOR UPDATE
+        "#,
+    )
+    .bind(&new_tx.chain_id)
+    .bind(&new_tx.sender)
+    .bind(&new_tx.nonce_key)
+    .bind(&new_tx.nonce)
+    .bind(&new_tx.tx_hash)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_optional(tx.as_mut())
+    .await?;
+
+    Ok(record)
+}
+
+/// Marks the transaction replaced along with any fee ladder rungs still waiting behind it.
+pub async fn mark_replaced(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i64,
+    replaced_by: &[u8],
+) -> Result<Vec<TxRecord>> {
+    let record = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            replaced_by = $2,
+            next_action_at = NULL,
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE id = $3
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::Replaced.as_str())
+    .bind(replaced_by)
+    .bind(id)
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    let mut rows = match record.ladder_id {
+        Some(ladder_id) => {
+            sqlx::query_as::<_, TxRecord>(
+                r#"
+                UPDATE txs
+                SET status = $1,
+                    replaced_by = $2,
+                    next_action_at = NULL,
+                    updated_at = NOW()
+                WHERE ladder_id = $3
+                  AND status = $4
+                RETURNING *
+                "#,
+            )
+            .bind(TxStatus::Replaced.as_str())
+            .bind(replaced_by)
+            .bind(ladder_id)
+            .bind(TxStatus::Standby.as_str())
+            .fetch_all(tx.as_mut())
+            .await?
+        }
+        None => Vec::new(),
+    };
+    rows.insert(0, record);
+
+    for record in &rows {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+
+    Ok(rows)
+}
+
+pub async fn insert_fee_ladder(
+    tx: &mut Transaction<'_, Postgres>,
+    id: Uuid,
+    chain_id: u64,
+    escalate_after_attempts: i32,
+    escalate_after_seconds: i64,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO fee_ladders (id, chain_id, escalate_after_attempts, escalate_after_seconds)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(id)
+    .bind(PgU64::from(chain_id))
+    .bind(escalate_after_attempts)
+    .bind(escalate_after_seconds)
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_fee_ladder(pool: &PgPool, id: Uuid) -> Result<Option<FeeLadderRecord>> {
+    let record = sqlx::query_as::<_, FeeLadderRecord>("SELECT * FROM fee_ladders WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(record)
+}
+
+pub async fn list_fee_ladder_txs(
+    tx: &mut Transaction<'_, Postgres>,
+    ladder_id: Uuid,
+) -> Result<Vec<TxRecord>> {
+    let rows = sqlx::query_as::<_, TxRecord>(
+        "SELECT * FROM txs WHERE ladder_id = $1 ORDER BY ladder_rung ASC",
+    )
+    .bind(ladder_id)
+    .fetch_all(tx.as_mut())
+    .await?;
+    Ok(rows)
+}
+
+/// Swaps the leased rung for the next one on standby: the leased rung becomes `replaced` and the
+/// next rung is queued immediately. Returns `None` when there is no higher rung left or the lease
+/// was lost.
+pub async fn escalate_fee_ladder(
+    pool: &PgPool,
+    record: &TxRecord,
+    lease_owner: &str,
+) -> Result<Option<(TxRecord, TxRecord)>> {
+    let (Some(ladder_id), Some(rung)) = (record.ladder_id, record.ladder_rung) else {
+        return Ok(None);
+    };
+
+    let mut tx = pool.begin().await?;
+    let next = sqlx::query_as::<_, TxRecord>(
+        r#"
+        SELECT *
+        FROM txs
+        WHERE ladder_id = $1
+          AND ladder_rung > $2
+          AND status = $3
+        ORDER BY ladder_rung ASC
+        LIMIT 1
+        FOR UPDATE
+        "#,
+    )
+    .bind(ladder_id)
+    .bind(rung)
+    .bind(TxStatus::Standby.as_str())
+    .fetch_optional(tx.as_mut())
+    .await?;
+    let Some(next) = next else {
+        return Ok(None);
+    };
+
+    let replaced = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            replaced_by = $2,
+            next_action_at = NULL,
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE id = $3
+          AND status = $4
+          AND lease_owner = $5
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::Replaced.as_str())
+    .bind(&next.tx_hash)
+    .bind(record.id)
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(lease_owner)
+    .fetch_optional(tx.as_mut())
+    .await?;
+    let Some(replaced) = replaced else {
+        return Ok(None);
+    };
+
+    let promoted = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            eligible_at = GREATEST(eligible_at, NOW()),
+            next_action_at = GREATEST(eligible_at, NOW()),
+            updated_at = NOW()
+        WHERE id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::Queued.as_str())
+    .bind(next.id)
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    enqueue_webhook_events(tx.as_mut(), &replaced).await?;
+    tx.commit().await?;
+
+    Ok(Some((replaced, promoted)))
+}
+
+/// Retires every other pending transaction with `winner`'s nonce once `winner`, a transaction
+/// that had been replaced, is mined after all.
+pub async fn retire_replacements(pool: &PgPool, winner: &TxRecord) -> Result<Vec<TxRecord>> {
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            replaced_by = $2,
+            next_action_at = NULL,
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE chain_id = $3
+          AND sender = $4
+          AND nonce_key = $5
+          AND nonce = $6
+          AND id != $7
+          AND status IN ($8, $9, $10, $11, $12)
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::Replaced.as_str())
+    .bind(&winner.tx_hash)
+    .bind(&winner.chain_id)
+    .bind(&winner.sender)
+    .bind(&winner.nonce_key)
+    .bind(&winner.nonce)
+    .bind(winner.id)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    for record in &rows {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
+/// Retires every other pending rung of `winner`'s ladder once `winner` is mined.
+pub async fn retire_fee_ladder(pool: &PgPool, winner: &TxRecord) -> Result<Vec<TxRecord>> {
+    let Some(ladder_id) = winner.ladder_id else {
+        return Ok(Vec::new());
+    };
+
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            replaced_by = $2,
+            next_action_at = NULL,
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE ladder_id = $3
+          AND id != $4
+          AND status IN ($5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::Replaced.as_str())
+    .bind(&winner.tx_hash)
+    .bind(ladder_id)
+    .bind(winner.id)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    for record in &rows {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
 pub async fn get_group_nonce_key(
     tx: &mut Transaction<'_, Postgres>,
     chain_id: u64,
@@ -113,6 +448,63 @@ pub async fn get_group_nonce_windows(
     Ok(rows)
 }
 
+/// A row that belongs in the Redis schedule. Accepted `broadcasting` rows wait in the retry ZSET
+/// for their rebroadcast.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScheduledTx {
+    pub tx_hash: Vec<u8>,
+    pub status: String,
+    pub next_action_at: DateTime<Utc>,
+}
+
+pub async fn list_scheduled_txs(pool: &PgPool, chain_id: u64) -> Result<Vec<ScheduledTx>> {
+    let chain_id = PgU64::from(chain_id);
+    let rows = sqlx::query_as::<_, ScheduledTx>(
+        r#"
+        SELECT tx_hash, status, next_action_at
+        FROM txs
+        WHERE chain_id = $1
+          AND status IN ($2, $3, $4)
+          AND next_action_at IS NOT NULL
+        "#,
+    )
+    .bind(chain_id)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Earliest `next_action_at` after `now` among the chain's schedulable rows.
+pub async fn next_action_after(
+    pool: &PgPool,
+    chain_id: u64,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    let chain_id = PgU64::from(chain_id);
+    let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        r#"
+        SELECT MIN(next_action_at)
+        FROM txs
+        WHERE chain_id = $1
+          AND status IN ($2, $3, $4)
+          AND next_action_at > $5
+        "#,
+    )
+    .bind(chain_id)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(now)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(next)
+}
+
 pub async fn get_tx_by_hash(
     pool: &PgPool,
     chain_id: Option<u64>,
@@ -137,36 +529,232 @@ pub async fn get_tx_by_hash(
     Ok(record)
 }
 
-#[derive(Default, Debug, Clone)]
-pub struct TxFilters {
-    pub chain_id: Option<u64>,
-    pub sender: Option<Vec<u8>>,
-    pub group_id: Option<Vec<u8>>,
-    pub ungrouped: bool,
-    pub statuses: Vec<TxStatus>,
-    pub limit: i64,
+/// Looks up a transaction on `chain_id` inside a database transaction, so it sees rows inserted
+/// earlier in the same submission.
+pub async fn find_tx_by_hash(
+    tx: &mut Transaction<'_, Postgres>,
+    chain_id: u64,
+    tx_hash: &[u8],
+) -> Result<Option<TxRecord>> {
+    let chain_id = PgU64::from(chain_id);
+    let record =
+        sqlx::query_as::<_, TxRecord>("SELECT * FROM txs WHERE chain_id = $1 AND tx_hash = $2")
+            .bind(chain_id)
+            .bind(tx_hash)
+            .fetch_optional(tx.as_mut())
+            .await?;
+
+    Ok(record)
 }
 
-#[derive(Debug, Clone, sqlx::FromRow)]
-pub struct SenderGroupRecord {
-    pub chain_id: PgU64,
-    pub group_id: Vec<u8>,
-    pub nonce_key: Vec<u8>,
-    pub start_at: DateTime<Utc>,
-    pub end_at: DateTime<Utc>,
-    pub next_transaction_at: Option<DateTime<Utc>>,
-    pub next_transaction_raw_tx: Option<Vec<u8>>,
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    #[default]
+    CreatedAt,
+    EligibleAt,
+    Nonce,
+    UpdatedAt,
 }
 
-pub async fn list_txs(pool: &PgPool, filters: TxFilters) -> Result<Vec<TxRecord>> {
-    let mut qb = QueryBuilder::<Postgres>::new("SELECT * FROM txs WHERE 1=1");
+impl SortKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortKey::CreatedAt => "created_at",
+            SortKey::EligibleAt => "eligible_at",
+            SortKey::Nonce => "nonce",
+            SortKey::UpdatedAt => "updated_at",
+        }
+    }
 
-    if let Some(chain_id) = filters.chain_id {
-        let chain_id = PgU64::from(chain_id);
-        qb.push(" AND chain_id = ").push_bind(chain_id);
+    pub fn value_of(&self, record: &TxRecord) -> SortValue {
+        match self {
+            SortKey::CreatedAt => SortValue::Timestamp(record.created_at),
+            SortKey::EligibleAt => SortValue::Timestamp(record.eligible_at),
+            SortKey::Nonce => SortValue::Number(record.nonce.to_uint()),
+            SortKey::UpdatedAt => SortValue::Timestamp(record.updated_at),
+        }
     }
-    if let Some(sender) = filters.sender {
-        qb.push(" AND sender = ").push_bind(sender);
+
+    /// Group column ordered by this key; groups sort by their first transaction.
+    pub fn group_column(&self) -> Option<&'static str> {
+        match self {
+            SortKey::CreatedAt => Some("g.created_at"),
+            SortKey::EligibleAt => Some("g.start_at"),
+            SortKey::Nonce | SortKey::UpdatedAt => None,
+        }
+    }
+}
+
+impl TryFrom<&str> for SortKey {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "created_at" => Ok(SortKey::CreatedAt),
+            "eligible_at" => Ok(SortKey::EligibleAt),
+            "nonce" => Ok(SortKey::Nonce),
+            "updated_at" => Ok(SortKey::UpdatedAt),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+impl TryFrom<&str> for SortOrder {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortValue {
+    Timestamp(DateTime<Utc>),
+    Number(u64),
+}
+
+/// Position after the last row of a page: its sort value with the row id as tie-breaker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageCursor {
+    pub sort: SortKey,
+    pub order: SortOrder,
+    pub value: SortValue,
+    pub id: i64,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct TxFilters {
+    pub chain_id: Option<u64>,
+    pub sender: Option<Vec<u8>>,
+    pub fee_payer: Option<Vec<u8>>,
+    /// Matches transactions that call this address, directly or in a Tempo batch.
+    pub to: Option<Vec<u8>>,
+    pub group_id: Option<Vec<u8>>,
+    pub ungrouped: bool,
+    pub statuses: Vec<TxStatus>,
+    pub reverted: Option<bool>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub eligible_after: Option<DateTime<Utc>>,
+    pub eligible_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+    pub sort: SortKey,
+    pub order: SortOrder,
+    pub after: Option<PageCursor>,
+    pub limit: i64,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct GroupFilters {
+    pub chain_id: Option<u64>,
+    pub sender: Option<Vec<u8>>,
+    pub active_only: bool,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub sort: SortKey,
+    pub order: SortOrder,
+    pub after: Option<PageCursor>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SenderGroupRecord {
+    pub chain_id: PgU64,
+    pub group_id: Vec<u8>,
+    pub nonce_key: Vec<u8>,
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub next_transaction_at: Option<DateTime<Utc>>,
+    pub next_transaction_raw_tx: Option<Vec<u8>>,
+    /// When the group's first transaction was stored.
+    pub created_at: DateTime<Utc>,
+    /// Lowest transaction id in the group; breaks ties between groups when paging.
+    pub first_id: i64,
+}
+
+impl SenderGroupRecord {
+    pub fn sort_value(&self, sort: SortKey) -> SortValue {
+        match sort {
+            SortKey::EligibleAt => SortValue::Timestamp(self.start_at),
+            _ => SortValue::Timestamp(self.created_at),
+        }
+    }
+}
+
+/// Restricts the query to rows after `cursor` in `(column, id_column)` order.
+fn push_keyset(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    column: &str,
+    id_column: &str,
+    cursor: &PageCursor,
+) {
+    let op = match cursor.order {
+        SortOrder::Asc => ">",
+        SortOrder::Desc => "<",
+    };
+    qb.push(format!(" AND ({column}, {id_column}) {op} ("));
+    match &cursor.value {
+        SortValue::Timestamp(value) => qb.push_bind(*value),
+        SortValue::Number(value) => qb.push_bind(PgU64::from(*value)),
+    };
+    qb.push(", ").push_bind(cursor.id).push(")");
+}
+
+fn push_time_range(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    column: &str,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) {
+    if let Some(after) = after {
+        qb.push(format!(" AND {column} >= ")).push_bind(after);
+    }
+    if let Some(before) = before {
+        qb.push(format!(" AND {column} < ")).push_bind(before);
+    }
+}
+
+/// Returns up to `filters.limit` rows plus one more when another page follows.
+pub async fn list_txs(pool: &PgPool, filters: TxFilters) -> Result<Vec<TxRecord>> {
+    let mut qb = QueryBuilder::<Postgres>::new("SELECT * FROM txs WHERE 1=1");
+
+    if let Some(chain_id) = filters.chain_id {
+        let chain_id = PgU64::from(chain_id);
+        qb.push(" AND chain_id = ").push_bind(chain_id);
+    }
+    if let Some(sender) = filters.sender {
+        qb.push(" AND sender = ").push_bind(sender);
+    }
+    if let Some(fee_payer) = filters.fee_payer {
+        qb.push(" AND fee_payer = ").push_bind(fee_payer);
+    }
+    if let Some(to) = filters.to {
+        qb.push(
+            " AND EXISTS (SELECT 1 FROM tx_call_targets c WHERE c.tx_id = txs.id AND c.target = ",
+        )
+        .push_bind(to)
+        .push(")");
     }
     if let Some(group_id) = filters.group_id {
         qb.push(" AND group_id = ").push_bind(group_id);
@@ -182,20 +770,46 @@ pub async fn list_txs(pool: &PgPool, filters: TxFilters) -> Result<Vec<TxRecord>
         }
         qb.push(")");
     }
+    if let Some(reverted) = filters.reverted {
+        qb.push(" AND receipt_success = ").push_bind(!reverted);
+    }
+    push_time_range(
+        &mut qb,
+        "created_at",
+        filters.created_after,
+        filters.created_before,
+    );
+    push_time_range(
+        &mut qb,
+        "eligible_at",
+        filters.eligible_after,
+        filters.eligible_before,
+    );
+    push_time_range(
+        &mut qb,
+        "updated_at",
+        filters.updated_after,
+        filters.updated_before,
+    );
+
+    let column = filters.sort.as_str();
+    if let Some(cursor) = &filters.after {
+        push_keyset(&mut qb, column, "id", cursor);
+    }
 
-    let limit = filters.limit.clamp(1, 500);
-    qb.push(" ORDER BY created_at DESC LIMIT ").push_bind(limit);
+    let order = filters.order.as_str();
+    let limit = filters.limit.clamp(1, 500) + 1;
+    qb.push(format!(" ORDER BY {column} {order}, id {order} LIMIT "))
+        .push_bind(limit);
 
     let records = qb.build_query_as::<TxRecord>().fetch_all(pool).await?;
     Ok(records)
 }
 
+/// Returns up to `filters.limit` groups plus one more when another page follows.
 pub async fn list_sender_groups(
     pool: &PgPool,
-    sender: &Option<Vec<u8>>,
-    chain_id: Option<u64>,
-    limit: i64,
-    active_only: bool,
+    filters: GroupFilters,
 ) -> Result<Vec<SenderGroupRecord>> {
     let mut qb = QueryBuilder::<Postgres>::new(
         "WITH group_agg AS ( \
@@ -206,6 +820,8 @@ pub async fn list_sender_groups(
         (ARRAY_AGG(nonce_key ORDER BY created_at))[1] AS nonce_key, \
         MIN(eligible_at) AS start_at, \
         MAX(eligible_at) AS end_at, \
+        MIN(created_at) AS created_at, \
+        MIN(id) AS first_id, \
         MIN(eligible_at) FILTER (WHERE status IN (",
     );
     let mut statuses = qb.separated(", ");
@@ -220,19 +836,24 @@ pub async fn list_sender_groups(
         ")) AS next_transaction_at \
         FROM txs \
         WHERE group_id IS NOT NULL \
-          AND status != ",
+          AND status NOT IN (",
     );
     qb.push_bind(TxStatus::CanceledLocally.as_str());
-    if let Some(sender) = sender {
-        qb.push(" AND sender = ").push_bind(sender.clone());
+    qb.push(", ");
+    qb.push_bind(TxStatus::Replaced.as_str());
+    qb.push(", ");
+    qb.push_bind(TxStatus::Standby.as_str());
+    qb.push(")");
+    if let Some(sender) = filters.sender {
+        qb.push(" AND sender = ").push_bind(sender);
     }
-    if let Some(chain_id) = chain_id {
+    if let Some(chain_id) = filters.chain_id {
         let chain_id = PgU64::from(chain_id);
         qb.push(" AND chain_id = ").push_bind(chain_id);
     }
 
     qb.push(" GROUP BY chain_id, group_id, sender");
-    if active_only {
+    if filters.active_only {
         qb.push(" HAVING MAX(eligible_at) > NOW()");
     }
     qb.push(
@@ -244,17 +865,37 @@ pub async fn list_sender_groups(
         g.start_at, \
         g.end_at, \
         g.next_transaction_at, \
+        g.created_at, \
+        g.first_id, \
         t.raw_tx AS next_transaction_raw_tx \
         FROM group_agg g \
         LEFT JOIN txs t ON t.chain_id = g.chain_id \
             AND t.sender = g.sender \
             AND t.group_id = g.group_id \
-            AND t.eligible_at = g.next_transaction_at",
+            AND t.eligible_at = g.next_transaction_at \
+        WHERE 1=1",
+    );
+    push_time_range(
+        &mut qb,
+        "g.created_at",
+        filters.created_after,
+        filters.created_before,
     );
 
-    let limit = limit.clamp(1, 500);
-    qb.push(" ORDER BY g.chain_id, g.group_id LIMIT ")
-        .push_bind(limit);
+    let column = filters
+        .sort
+        .group_column()
+        .ok_or_else(|| anyhow::anyhow!("groups cannot be sorted by {}", filters.sort.as_str()))?;
+    if let Some(cursor) = &filters.after {
+        push_keyset(&mut qb, column, "g.first_id", cursor);
+    }
+
+    let order = filters.order.as_str();
+    let limit = filters.limit.clamp(1, 500) + 1;
+    qb.push(format!(
+        " ORDER BY {column} {order}, g.first_id {order} LIMIT "
+    ))
+    .push_bind(limit);
 
     let rows = qb
         .build_query_as::<SenderGroupRecord>()
@@ -270,7 +911,7 @@ pub async fn list_active_txs(pool: &PgPool, chain_id: u64) -> Result<Vec<TxRecor
         SELECT *
         FROM txs
         WHERE chain_id = $1
-          AND status IN ($2, $3, $4)
+          AND status IN ($2, $3, $4, $5, $6)
         ORDER BY next_action_at ASC NULLS LAST, created_at ASC
         "#,
     )
@@ -278,6 +919,147 @@ pub async fn list_active_txs(pool: &PgPool, chain_id: u64) -> Result<Vec<TxRecor
     .bind(TxStatus::Queued.as_str())
     .bind(TxStatus::Broadcasting.as_str())
     .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Executed transactions not yet `watcher.confirmations` blocks deep.
+pub async fn list_unconfirmed_txs(pool: &PgPool, chain_id: u64) -> Result<Vec<TxRecord>> {
+    let chain_id = PgU64::from(chain_id);
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        SELECT *
+        FROM txs
+        WHERE chain_id = $1
+          AND status = $2
+        ORDER BY receipt_block_number ASC NULLS FIRST
+        "#,
+    )
+    .bind(chain_id)
+    .bind(TxStatus::Executed.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Transactions replaced after they were broadcast while their nonce is still pending: escalated
+/// fee ladder rungs and replaced transactions stay in the mempool and may still be mined.
+pub async fn list_replaced_in_flight(pool: &PgPool, chain_id: u64) -> Result<Vec<TxRecord>> {
+    let chain_id = PgU64::from(chain_id);
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        SELECT r.*
+        FROM txs r
+        WHERE r.chain_id = $1
+          AND r.status = $2
+          AND r.attempts > 0
+          AND EXISTS (
+            SELECT 1
+            FROM txs p
+            WHERE p.chain_id = r.chain_id
+              AND p.sender = r.sender
+              AND p.nonce_key = r.nonce_key
+              AND p.nonce = r.nonce
+              AND p.status IN ($3, $4, $5, $6)
+          )
+        "#,
+    )
+    .bind(chain_id)
+    .bind(TxStatus::Replaced.as_str())
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Replaced transactions with `record`'s nonce that were broadcast before they were replaced.
+pub async fn list_broadcast_replaced_at_nonce(
+    pool: &PgPool,
+    record: &TxRecord,
+) -> Result<Vec<TxRecord>> {
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        SELECT *
+        FROM txs
+        WHERE chain_id = $1
+          AND sender = $2
+          AND nonce_key = $3
+          AND nonce = $4
+          AND id != $5
+          AND status = $6
+          AND attempts > 0
+        ORDER BY id DESC
+        "#,
+    )
+    .bind(&record.chain_id)
+    .bind(&record.sender)
+    .bind(&record.nonce_key)
+    .bind(&record.nonce)
+    .bind(record.id)
+    .bind(TxStatus::Replaced.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Makes transactions that were waiting behind `current_nonce` due now, up to the look-ahead
+/// window, so they don't sit out a retry backoff earned while their predecessor was pending.
+pub async fn wake_nonce_successors(
+    pool: &PgPool,
+    chain_id: u64,
+    sender: &[u8],
+    nonce_key: &[u8],
+    current_nonce: u64,
+    nonce_lookahead: u32,
+) -> Result<Vec<TxRecord>> {
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET next_action_at = NOW(),
+            updated_at = NOW()
+        WHERE chain_id = $1
+          AND sender = $2
+          AND nonce_key = $3
+          AND nonce >= $4
+          AND nonce <= $4 + $5
+          AND status IN ($6, $7)
+          AND eligible_at <= NOW()
+          AND next_action_at > NOW()
+          AND lease_owner IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(PgU64::from(chain_id))
+    .bind(sender)
+    .bind(nonce_key)
+    .bind(PgU64::from(current_nonce))
+    .bind(i64::from(nonce_lookahead))
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn count_txs_by_status(pool: &PgPool) -> Result<Vec<TxStatusCount>> {
+    let rows = sqlx::query_as::<_, TxStatusCount>(
+        r#"
+        SELECT chain_id, status, COUNT(*) AS count
+        FROM txs
+        GROUP BY chain_id, status
+        "#,
+    )
     .fetch_all(pool)
     .await?;
 
@@ -303,7 +1085,16 @@ pub async fn get_group_txs(
     Ok(rows)
 }
 
-pub async fn cancel_group(pool: &PgPool, sender: &[u8], group_id: &[u8]) -> Result<Vec<TxRecord>> {
+/// Cancels the group's transactions with a nonce in `nonces`, on `chain_id` only when given.
+pub async fn cancel_group(
+    pool: &PgPool,
+    sender: &[u8],
+    group_id: &[u8],
+    chain_id: Option<u64>,
+    nonces: RangeInclusive<u64>,
+) -> Result<Vec<TxRecord>> {
+    let chain_id = chain_id.map(PgU64::from);
+    let mut tx = pool.begin().await?;
     let rows = sqlx::query_as::<_, TxRecord>(
         r#"
         UPDATE txs
@@ -313,111 +1104,317 @@ pub async fn cancel_group(pool: &PgPool, sender: &[u8], group_id: &[u8]) -> Resu
             lease_owner = NULL,
             lease_until = NULL,
             updated_at = NOW()
-        WHERE sender = $2 AND group_id = $3
+        WHERE sender = $2
+          AND group_id = $3
+          AND ($4::NUMERIC IS NULL OR chain_id = $4)
+          AND nonce BETWEEN $5 AND $6
+          AND status IN ($7, $8, $9, $10, $11)
         RETURNING *
         "#,
     )
     .bind(TxStatus::CanceledLocally.as_str())
     .bind(sender)
     .bind(group_id)
-    .fetch_all(pool)
+    .bind(chain_id)
+    .bind(PgU64::from(*nonces.start()))
+    .bind(PgU64::from(*nonces.end()))
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(tx.as_mut())
     .await?;
 
+    for record in &rows {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
     Ok(rows)
 }
 
-pub async fn lease_due_txs(
+/// Holds the group's pending transactions on `chain_id` back from the scheduler. Leases are
+/// dropped, so a broadcast in flight does not reschedule the row.
+pub async fn pause_group(
     pool: &PgPool,
+    sender: &[u8],
+    group_id: &[u8],
     chain_id: u64,
-    now: DateTime<Utc>,
-    lease_owner: &str,
-    lease_until: DateTime<Utc>,
-    limit: i64,
 ) -> Result<Vec<TxRecord>> {
     let chain_id = PgU64::from(chain_id);
+    let mut tx = pool.begin().await?;
     let rows = sqlx::query_as::<_, TxRecord>(
         r#"
-        WITH due AS (
-            SELECT id
-            FROM txs
-            WHERE chain_id = $1
-              AND status IN ($2, $3, $4)
-              AND next_action_at <= $5
-              AND (lease_until IS NULL OR lease_until < $5)
-            ORDER BY next_action_at ASC
-            LIMIT $6
-            FOR UPDATE SKIP LOCKED
-        )
         UPDATE txs
-        SET status = $7,
-            lease_owner = $8,
-            lease_until = $9,
+        SET status = $1,
+            next_action_at = NULL,
+            lease_owner = NULL,
+            lease_until = NULL,
             updated_at = NOW()
-        WHERE id IN (SELECT id FROM due)
+        WHERE sender = $2
+          AND group_id = $3
+          AND chain_id = $4
+          AND status IN ($5, $6, $7)
         RETURNING *
         "#,
     )
+    .bind(TxStatus::Paused.as_str())
+    .bind(sender)
+    .bind(group_id)
     .bind(chain_id)
     .bind(TxStatus::Queued.as_str())
-    .bind(TxStatus::RetryScheduled.as_str())
-    .bind(TxStatus::Broadcasting.as_str())
-    .bind(now)
-    .bind(limit)
     .bind(TxStatus::Broadcasting.as_str())
-    .bind(lease_owner)
-    .bind(lease_until)
-    .fetch_all(pool)
+    .bind(TxStatus::RetryScheduled.as_str())
+    .fetch_all(tx.as_mut())
     .await?;
 
+    for record in &rows {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
     Ok(rows)
 }
 
-pub async fn lease_tx_by_hash(
+/// Returns the group's paused transactions to the schedule, due at their `eligible_at` or now if
+/// that has passed. Transactions that were never broadcast are `queued` again, the rest
+/// `retry_scheduled`.
+pub async fn resume_group(
     pool: &PgPool,
+    sender: &[u8],
+    group_id: &[u8],
     chain_id: u64,
-    tx_hash: &[u8],
-    now: DateTime<Utc>,
-    lease_owner: &str,
-    lease_until: DateTime<Utc>,
-) -> Result<Option<TxRecord>> {
+) -> Result<Vec<TxRecord>> {
     let chain_id = PgU64::from(chain_id);
-    let row = sqlx::query_as::<_, TxRecord>(
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, TxRecord>(
         r#"
         UPDATE txs
-        SET status = $1,
-            lease_owner = $2,
-            lease_until = $3,
+        SET status = CASE WHEN attempts = 0 THEN $1 ELSE $2 END,
+            next_action_at = GREATEST(eligible_at, NOW()),
             updated_at = NOW()
-        WHERE chain_id = $4
-          AND tx_hash = $5
-          AND status IN ($6, $7, $8)
-          AND next_action_at <= $9
-          AND (lease_until IS NULL OR lease_until < $9)
+        WHERE sender = $3
+          AND group_id = $4
+          AND chain_id = $5
+          AND status = $6
         RETURNING *
         "#,
     )
-    .bind(TxStatus::Broadcasting.as_str())
-    .bind(lease_owner)
-    .bind(lease_until)
-    .bind(chain_id)
-    .bind(tx_hash)
     .bind(TxStatus::Queued.as_str())
     .bind(TxStatus::RetryScheduled.as_str())
-    .bind(TxStatus::Broadcasting.as_str())
-    .bind(now)
-    .fetch_optional(pool)
+    .bind(sender)
+    .bind(group_id)
+    .bind(chain_id)
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(tx.as_mut())
     .await?;
 
-    Ok(row)
+    for record in &rows {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(rows)
 }
 
-pub async fn reschedule_tx(
-    pool: &PgPool,
-    id: i64,
-    status: &str,
-    next_action_at: DateTime<Utc>,
-    attempts: i32,
-    last_error: Option<&str>,
+/// Cancels one transaction locally, along with any fee ladder rungs still on standby behind it.
+/// Returns nothing when the transaction already reached a terminal status.
+pub async fn cancel_tx(pool: &PgPool, id: i64) -> Result<Vec<TxRecord>> {
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        WITH target AS (
+            SELECT id, ladder_id
+            FROM txs
+            WHERE id = $2
+              AND status NOT IN ($3, $4, $5, $6, $7, $8, $9)
+            FOR UPDATE
+        )
+        UPDATE txs
+        SET status = $1,
+            raw_tx = NULL,
+            next_action_at = NULL,
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        FROM target
+        WHERE txs.id = target.id
+           OR (txs.ladder_id = target.ladder_id AND txs.status = $10)
+        RETURNING txs.*
+        "#,
+    )
+    .bind(TxStatus::CanceledLocally.as_str())
+    .bind(id)
+    .bind(TxStatus::Executed.as_str())
+    .bind(TxStatus::Confirmed.as_str())
+    .bind(TxStatus::Expired.as_str())
+    .bind(TxStatus::Invalid.as_str())
+    .bind(TxStatus::StaleByNonce.as_str())
+    .bind(TxStatus::CanceledLocally.as_str())
+    .bind(TxStatus::Replaced.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    for record in &rows {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
+/// Records a cancel authorization nonce as used. Returns false when the sender already used it.
+/// The sender's expired nonces are dropped first; their authorizations are rejected anyway.
+pub async fn use_auth_nonce(
+    pool: &PgPool,
+    sender: &[u8],
+    nonce: &[u8],
+    expires_at: DateTime<Utc>,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM auth_nonces WHERE sender = $1 AND expires_at < NOW()")
+        .bind(sender)
+        .execute(tx.as_mut())
+        .await?;
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO auth_nonces (sender, nonce, expires_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (sender, nonce) DO NOTHING
+        "#,
+    )
+    .bind(sender)
+    .bind(nonce)
+    .bind(expires_at)
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected();
+    tx.commit().await?;
+
+    Ok(inserted == 1)
+}
+
+/// Leases due transactions. A transaction is held back while a pending transaction with the same
+/// sender and nonce key sits more than `nonce_lookahead` nonces below it.
+pub async fn lease_due_txs(
+    pool: &PgPool,
+    chain_id: u64,
+    now: DateTime<Utc>,
+    lease_owner: &str,
+    lease_until: DateTime<Utc>,
+    limit: i64,
+    nonce_lookahead: u32,
+) -> Result<Vec<TxRecord>> {
+    let chain_id = PgU64::from(chain_id);
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        WITH due AS (
+            SELECT id
+            FROM txs t
+            WHERE t.chain_id = $1
+              AND t.status IN ($2, $3, $4)
+              AND t.next_action_at <= $5
+              AND (t.lease_until IS NULL OR t.lease_until < $5)
+              AND NOT EXISTS (
+                SELECT 1
+                FROM txs p
+                WHERE p.chain_id = t.chain_id
+                  AND p.sender = t.sender
+                  AND p.nonce_key = t.nonce_key
+                  AND p.nonce + $10 < t.nonce
+                  AND p.status IN ($2, $3, $4, $11)
+              )
+            ORDER BY t.next_action_at ASC
+            LIMIT $6
+            FOR UPDATE OF t SKIP LOCKED
+        )
+        UPDATE txs
+        SET status = $7,
+            lease_owner = $8,
+            lease_until = $9,
+            updated_at = NOW()
+        WHERE id IN (SELECT id FROM due)
+        RETURNING *
+        "#,
+    )
+    .bind(chain_id)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(now)
+    .bind(limit)
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(lease_owner)
+    .bind(lease_until)
+    .bind(i64::from(nonce_lookahead))
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Leases one transaction if it is due, under the same nonce ordering as [`lease_due_txs`].
+pub async fn lease_tx_by_hash(
+    pool: &PgPool,
+    chain_id: u64,
+    tx_hash: &[u8],
+    now: DateTime<Utc>,
+    lease_owner: &str,
+    lease_until: DateTime<Utc>,
+    nonce_lookahead: u32,
+) -> Result<Option<TxRecord>> {
+    let chain_id = PgU64::from(chain_id);
+    let row = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs t
+        SET status = $1,
+            lease_owner = $2,
+            lease_until = $3,
+            updated_at = NOW()
+        WHERE t.chain_id = $4
+          AND t.tx_hash = $5
+          AND t.status IN ($6, $7, $8)
+          AND t.next_action_at <= $9
+          AND (t.lease_until IS NULL OR t.lease_until < $9)
+          AND NOT EXISTS (
+            SELECT 1
+            FROM txs p
+            WHERE p.chain_id = t.chain_id
+              AND p.sender = t.sender
+              AND p.nonce_key = t.nonce_key
+              AND p.nonce + $10 < t.nonce
+              AND p.status IN ($6, $7, $8, $11)
+          )
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(lease_owner)
+    .bind(lease_until)
+    .bind(chain_id)
+    .bind(tx_hash)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(now)
+    .bind(i64::from(nonce_lookahead))
+    .bind(TxStatus::Paused.as_str())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn reschedule_tx(
+    pool: &PgPool,
+    id: i64,
+    status: &str,
+    next_action_at: DateTime<Utc>,
+    attempts: i32,
+    last_error: Option<&str>,
 ) -> Result<()> {
     sqlx::query(
         r#"
@@ -452,8 +1449,8 @@ pub async fn reschedule_tx_if_leased(
     next_action_at: DateTime<Utc>,
     attempts: i32,
     last_error: Option<&str>,
-) -> Result<bool> {
-    let result = sqlx::query(
+) -> Result<Option<TxRecord>> {
+    let row = sqlx::query_as::<_, TxRecord>(
         r#"
         UPDATE txs
         SET status = $1,
@@ -467,6 +1464,7 @@ pub async fn reschedule_tx_if_leased(
         WHERE id = $5
           AND status = $6
           AND lease_owner = $7
+        RETURNING *
         "#,
     )
     .bind(status)
@@ -476,10 +1474,10 @@ pub async fn reschedule_tx_if_leased(
     .bind(id)
     .bind(TxStatus::Broadcasting.as_str())
     .bind(lease_owner)
-    .execute(pool)
+    .fetch_optional(pool)
     .await?;
 
-    Ok(result.rows_affected() > 0)
+    Ok(row)
 }
 
 pub async fn mark_terminal(
@@ -487,8 +1485,9 @@ pub async fn mark_terminal(
     id: i64,
     status: &str,
     last_error: Option<&str>,
-) -> Result<()> {
-    sqlx::query(
+) -> Result<Option<TxRecord>> {
+    let mut tx = pool.begin().await?;
+    let record = sqlx::query_as::<_, TxRecord>(
         r#"
         UPDATE txs
         SET status = $1,
@@ -498,15 +1497,21 @@ pub async fn mark_terminal(
             lease_until = NULL,
             updated_at = NOW()
         WHERE id = $3
+        RETURNING *
         "#,
     )
     .bind(status)
     .bind(last_error)
     .bind(id)
-    .execute(pool)
+    .fetch_optional(tx.as_mut())
     .await?;
 
-    Ok(())
+    if let Some(record) = &record {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(record)
 }
 
 pub async fn mark_terminal_if_leased(
@@ -515,8 +1520,9 @@ pub async fn mark_terminal_if_leased(
     lease_owner: &str,
     status: &str,
     last_error: Option<&str>,
-) -> Result<bool> {
-    let result = sqlx::query(
+) -> Result<Option<TxRecord>> {
+    let mut tx = pool.begin().await?;
+    let record = sqlx::query_as::<_, TxRecord>(
         r#"
         UPDATE txs
         SET status = $1,
@@ -528,6 +1534,7 @@ pub async fn mark_terminal_if_leased(
         WHERE id = $3
           AND status = $4
           AND lease_owner = $5
+        RETURNING *
         "#,
     )
     .bind(status)
@@ -535,43 +1542,137 @@ pub async fn mark_terminal_if_leased(
     .bind(id)
     .bind(TxStatus::Broadcasting.as_str())
     .bind(lease_owner)
-    .execute(pool)
+    .fetch_optional(tx.as_mut())
     .await?;
 
-    Ok(result.rows_affected() > 0)
+    if let Some(record) = &record {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(record)
 }
 
-pub async fn mark_executed(pool: &PgPool, id: i64, receipt: serde_json::Value) -> Result<()> {
-    sqlx::query(
+pub async fn mark_executed(
+    pool: &PgPool,
+    id: i64,
+    outcome: &ReceiptOutcome,
+) -> Result<Option<TxRecord>> {
+    let block = outcome.block.as_ref();
+    let mut tx = pool.begin().await?;
+    let record = sqlx::query_as::<_, TxRecord>(
         r#"
         UPDATE txs
         SET status = $1,
             receipt = $2,
+            receipt_block_number = $3,
+            receipt_block_hash = $4,
+            receipt_success = $5,
+            receipt_gas_used = $6,
+            receipt_effective_gas_price = $7,
+            receipt_fee_token = $8,
+            replaced_by = NULL,
             next_action_at = NULL,
             lease_owner = NULL,
             lease_until = NULL,
             updated_at = NOW()
-        WHERE id = $3
+        WHERE id = $9
+        RETURNING *
         "#,
     )
     .bind(TxStatus::Executed.as_str())
-    .bind(receipt)
+    .bind(&outcome.receipt)
+    .bind(block.map(|block| PgU64::from(block.number)))
+    .bind(block.map(|block| block.hash.as_slice()))
+    .bind(outcome.success)
+    .bind(PgU64::from(outcome.gas_used))
+    .bind(PgU128::from(outcome.effective_gas_price))
+    .bind(&outcome.fee_token)
     .bind(id)
-    .execute(pool)
+    .fetch_optional(tx.as_mut())
     .await?;
 
-    Ok(())
+    if let Some(record) = &record {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(record)
 }
 
-pub async fn mark_expired(pool: &PgPool, id: i64) -> Result<()> {
+pub async fn mark_confirmed(pool: &PgPool, id: i64) -> Result<Option<TxRecord>> {
+    let mut tx = pool.begin().await?;
+    let record = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            updated_at = NOW()
+        WHERE id = $2
+          AND status = $3
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::Confirmed.as_str())
+    .bind(id)
+    .bind(TxStatus::Executed.as_str())
+    .fetch_optional(tx.as_mut())
+    .await?;
+
+    if let Some(record) = &record {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(record)
+}
+
+/// Sends an executed transaction whose block left the canonical chain back to the scheduler.
+/// `raw_tx` is kept on execution, so it can be broadcast again as is.
+pub async fn mark_reorged(pool: &PgPool, id: i64) -> Result<Option<TxRecord>> {
+    let mut tx = pool.begin().await?;
+    let record = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            receipt = NULL,
+            receipt_block_number = NULL,
+            receipt_block_hash = NULL,
+            receipt_success = NULL,
+            receipt_gas_used = NULL,
+            receipt_effective_gas_price = NULL,
+            receipt_fee_token = NULL,
+            last_error = $2,
+            next_action_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $3
+          AND status = $4
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind("receipt block reorged out")
+    .bind(id)
+    .bind(TxStatus::Executed.as_str())
+    .fetch_optional(tx.as_mut())
+    .await?;
+
+    if let Some(record) = &record {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(record)
+}
+
+pub async fn mark_expired(pool: &PgPool, id: i64) -> Result<Option<TxRecord>> {
     mark_terminal(pool, id, TxStatus::Expired.as_str(), None).await
 }
 
-pub async fn mark_invalid(pool: &PgPool, id: i64, reason: &str) -> Result<()> {
+pub async fn mark_invalid(pool: &PgPool, id: i64, reason: &str) -> Result<Option<TxRecord>> {
     mark_terminal(pool, id, TxStatus::Invalid.as_str(), Some(reason)).await
 }
 
-pub async fn mark_stale_by_nonce(pool: &PgPool, id: i64) -> Result<()> {
+pub async fn mark_stale_by_nonce(pool: &PgPool, id: i64) -> Result<Option<TxRecord>> {
     mark_terminal(pool, id, TxStatus::StaleByNonce.as_str(), None).await
 }
 
@@ -596,3 +1697,579 @@ pub async fn recover_stuck_broadcasts(pool: &PgPool) -> Result<Vec<TxRecord>> {
 
     Ok(rows)
 }
+
+/// Makes a transaction due again, but not before its `valid_after`, and clears its lease. Only
+/// `invalid`, `retry_scheduled` and unleased `broadcasting` rows that still have their signed
+/// transaction and have not expired qualify; `None` is returned otherwise.
+pub async fn requeue_tx(pool: &PgPool, id: i64) -> Result<Option<TxRecord>> {
+    let mut tx = pool.begin().await?;
+    let record = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            next_action_at = GREATEST(eligible_at, NOW()),
+            last_error = NULL,
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE id = $2
+          AND (
+            status IN ($3, $1)
+            OR (status = $4 AND (lease_until IS NULL OR lease_until < NOW()))
+          )
+          AND raw_tx IS NOT NULL
+          AND (expires_at IS NULL OR expires_at > NOW())
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(id)
+    .bind(TxStatus::Invalid.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .fetch_optional(tx.as_mut())
+    .await?;
+
+    if let Some(record) = &record {
+        enqueue_webhook_events(tx.as_mut(), record).await?;
+    }
+    tx.commit().await?;
+
+    Ok(record)
+}
+
+/// Clears leases held by owners starting with `owner_prefix` and makes the rows due now.
+pub async fn release_leases(pool: &PgPool, owner_prefix: &str) -> Result<Vec<TxRecord>> {
+    let rows = sqlx::query_as::<_, TxRecord>(
+        r#"
+        UPDATE txs
+        SET status = $1,
+            next_action_at = NOW(),
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE status = $2
+          AND starts_with(lease_owner, $3)
+        RETURNING *
+        "#,
+    )
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(owner_prefix)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn insert_broadcast_attempts(
+    pool: &PgPool,
+    attempts: &[NewBroadcastAttempt],
+) -> Result<()> {
+    if attempts.is_empty() {
+        return Ok(());
+    }
+
+    let mut qb = QueryBuilder::<Postgres>::new(
+        "INSERT INTO broadcast_attempts (tx_id, attempt, endpoint, outcome, error, latency_ms) ",
+    );
+    qb.push_values(attempts, |mut row, attempt| {
+        row.push_bind(attempt.tx_id)
+            .push_bind(attempt.attempt)
+            .push_bind(&attempt.endpoint)
+            .push_bind(attempt.outcome.as_str())
+            .push_bind(&attempt.error)
+            .push_bind(attempt.latency_ms);
+    });
+    qb.build().execute(pool).await?;
+
+    Ok(())
+}
+
+pub async fn list_broadcast_attempts(
+    pool: &PgPool,
+    tx_id: i64,
+) -> Result<Vec<BroadcastAttemptRecord>> {
+    let rows = sqlx::query_as::<_, BroadcastAttemptRecord>(
+        r#"
+        SELECT *
+        FROM broadcast_attempts
+        WHERE tx_id = $1
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(tx_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Drops the signed bytes of up to `limit` rows in `status` last updated before `before`.
+/// `updated_at` is left alone so the row still ages towards archival. Rows whose nonce another
+/// transaction is still pending on are skipped here and by archival: a replaced transaction can
+/// still be mined until its nonce is used.
+pub async fn strip_raw_txs(
+    pool: &PgPool,
+    status: &str,
+    before: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE txs
+        SET raw_tx = NULL
+        WHERE id IN (
+            SELECT t.id
+            FROM txs t
+            WHERE t.status = $1
+              AND t.updated_at < $2
+              AND t.raw_tx IS NOT NULL
+              AND t.lease_owner IS NULL
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM txs p
+                  WHERE p.chain_id = t.chain_id
+                    AND p.sender = t.sender
+                    AND p.nonce_key = t.nonce_key
+                    AND p.nonce = t.nonce
+                    AND p.status IN ($4, $5, $6, $7, $8)
+              )
+            ORDER BY t.updated_at ASC
+            LIMIT $3
+            FOR UPDATE OF t SKIP LOCKED
+        )
+        "#,
+    )
+    .bind(status)
+    .bind(before)
+    .bind(limit)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Moves up to `limit` rows in `status` last updated before `before` into `txs_archive`.
+/// Call targets and broadcast attempts, whose rows are deleted with the transaction, are kept in
+/// the archived record.
+pub async fn archive_txs(
+    pool: &PgPool,
+    status: &str,
+    before: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        WITH archived AS (
+            DELETE FROM txs
+            WHERE id IN (
+                SELECT t.id
+                FROM txs t
+                WHERE t.status = $1
+                  AND t.updated_at < $2
+                  AND t.lease_owner IS NULL
+                  AND NOT EXISTS (
+                      SELECT 1
+                      FROM txs p
+                      WHERE p.chain_id = t.chain_id
+                        AND p.sender = t.sender
+                        AND p.nonce_key = t.nonce_key
+                        AND p.nonce = t.nonce
+                        AND p.status IN ($4, $5, $6, $7, $8)
+                  )
+                ORDER BY t.updated_at ASC
+                LIMIT $3
+                FOR UPDATE OF t SKIP LOCKED
+            )
+            RETURNING *
+        )
+        INSERT INTO txs_archive (id, chain_id, tx_hash, sender, status, created_at, updated_at, record)
+        SELECT a.id, a.chain_id, a.tx_hash, a.sender, a.status, a.created_at, a.updated_at,
+               to_jsonb(a) || jsonb_build_object(
+                   'call_targets',
+                   (SELECT COALESCE(jsonb_agg(c.target), '[]'::jsonb)
+                    FROM tx_call_targets c
+                    WHERE c.tx_id = a.id),
+                   'broadcast_attempts',
+                   (SELECT COALESCE(jsonb_agg(to_jsonb(b) - 'tx_id' ORDER BY b.id), '[]'::jsonb)
+                    FROM broadcast_attempts b
+                    WHERE b.tx_id = a.id)
+               )
+        FROM archived a
+        ON CONFLICT (id) DO NOTHING
+        "#,
+    )
+    .bind(status)
+    .bind(before)
+    .bind(limit)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Like [`archive_txs`], but hands the records to `write` as JSON text and only deletes them once
+/// it succeeds. The rows stay locked while `write` runs, so it should hand blocking work off the
+/// runtime. Records are rendered by Postgres so 128-bit amounts keep their precision.
+pub async fn export_txs<F, Fut>(
+    pool: &PgPool,
+    status: &str,
+    before: DateTime<Utc>,
+    limit: i64,
+    write: F,
+) -> Result<u64>
+where
+    F: FnOnce(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, (i64, String)>(
+        r#"
+        SELECT t.id,
+               to_jsonb(t) || jsonb_build_object(
+                   'call_targets',
+                   (SELECT COALESCE(jsonb_agg(c.target), '[]'::jsonb)
+                    FROM tx_call_targets c
+                    WHERE c.tx_id = t.id),
+                   'broadcast_attempts',
+                   (SELECT COALESCE(jsonb_agg(to_jsonb(b) - 'tx_id' ORDER BY b.id), '[]'::jsonb)
+                    FROM broadcast_attempts b
+                    WHERE b.tx_id = t.id)
+               )::text
+        FROM txs t
+        WHERE t.status = $1
+          AND t.updated_at < $2
+          AND t.lease_owner IS NULL
+          AND NOT EXISTS (
+              SELECT 1
+              FROM txs p
+              WHERE p.chain_id = t.chain_id
+                AND p.sender = t.sender
+                AND p.nonce_key = t.nonce_key
+                AND p.nonce = t.nonce
+                AND p.status IN ($4, $5, $6, $7, $8)
+          )
+        ORDER BY t.updated_at ASC
+        LIMIT $3
+        FOR UPDATE OF t SKIP LOCKED
+        "#,
+    )
+    .bind(status)
+    .bind(before)
+    .bind(limit)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Standby.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_all(tx.as_mut())
+    .await?;
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let (ids, records): (Vec<i64>, Vec<String>) = rows.into_iter().unzip();
+    write(records).await?;
+    let result = sqlx::query("DELETE FROM txs WHERE id = ANY($1)")
+        .bind(&ids)
+        .execute(tx.as_mut())
+        .await?;
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Removes fee ladders created before `before` whose rungs have all been archived.
+pub async fn delete_orphan_fee_ladders(pool: &PgPool, before: DateTime<Utc>) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM fee_ladders f
+        WHERE f.created_at < $1
+          AND NOT EXISTS (SELECT 1 FROM txs t WHERE t.ladder_id = f.id)
+        "#,
+    )
+    .bind(before)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Writes one outbox row per webhook subscribed to `record`, in the caller's transaction so the
+/// event is only recorded if the status change commits.
+async fn enqueue_webhook_events(conn: &mut PgConnection, record: &TxRecord) -> Result<u64> {
+    let event = TxEvent::from_record(record);
+    let payload = serde_json::to_value(&event)?;
+    let result = sqlx::query(
+        r#"
+        INSERT INTO webhook_deliveries (webhook_id, event_id, payload, status, next_attempt_at)
+        SELECT id, $1, $2, $3, NOW()
+        FROM webhooks
+        WHERE chain_id = $4
+          AND (
+            tx_hash = $5
+            OR (tx_hash IS NULL AND sender = $6 AND (group_id IS NULL OR group_id = $7))
+          )
+        "#,
+    )
+    .bind(event.id)
+    .bind(payload)
+    .bind(WebhookDeliveryStatus::Pending.as_str())
+    .bind(&record.chain_id)
+    .bind(&record.tx_hash)
+    .bind(&record.sender)
+    .bind(&record.group_id)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn insert_webhook(pool: &PgPool, webhook: &NewWebhook) -> Result<WebhookRecord> {
+    let record = sqlx::query_as::<_, WebhookRecord>(
+        r#"
+        INSERT INTO webhooks (id, url, secret, chain_id, sender, group_id, tx_hash)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&webhook.url)
+    .bind(&webhook.secret)
+    .bind(&webhook.chain_id)
+    .bind(&webhook.sender)
+    .bind(&webhook.group_id)
+    .bind(&webhook.tx_hash)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(record)
+}
+
+pub async fn get_webhook(pool: &PgPool, id: Uuid) -> Result<Option<WebhookRecord>> {
+    let record = sqlx::query_as::<_, WebhookRecord>("SELECT * FROM webhooks WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(record)
+}
+
+pub async fn delete_webhook(pool: &PgPool, id: Uuid) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn insert_api_key(pool: &PgPool, key: &NewApiKey) -> Result<ApiKeyRecord> {
+    let record = sqlx::query_as::<_, ApiKeyRecord>(
+        r#"
+        INSERT INTO api_keys (
+            id, name, key_hash, requests_per_minute, txs_per_day, max_queued_per_sender,
+            chain_ids, senders
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&key.name)
+    .bind(&key.key_hash)
+    .bind(key.requests_per_minute)
+    .bind(key.txs_per_day)
+    .bind(key.max_queued_per_sender)
+    .bind(&key.chain_ids)
+    .bind(&key.senders)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(record)
+}
+
+pub async fn get_active_api_key_by_hash(
+    pool: &PgPool,
+    key_hash: &[u8],
+) -> Result<Option<ApiKeyRecord>> {
+    let record = sqlx::query_as::<_, ApiKeyRecord>(
+        "SELECT * FROM api_keys WHERE key_hash = $1 AND revoked_at IS NULL",
+    )
+    .bind(key_hash)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
+
+pub async fn list_api_keys(pool: &PgPool) -> Result<Vec<ApiKeyRecord>> {
+    let records = sqlx::query_as::<_, ApiKeyRecord>("SELECT * FROM api_keys ORDER BY created_at")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(records)
+}
+
+pub async fn revoke_api_key(pool: &PgPool, id: Uuid) -> Result<bool> {
+    let result =
+        sqlx::query("UPDATE api_keys SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Counts a sender's transactions that are still waiting to be executed on `chain_id`.
+pub async fn count_pending_txs_for_sender(
+    pool: &PgPool,
+    chain_id: u64,
+    sender: &[u8],
+) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM txs
+        WHERE chain_id = $1
+          AND sender = $2
+          AND status IN ($3, $4, $5, $6)
+        "#,
+    )
+    .bind(PgU64::from(chain_id))
+    .bind(sender)
+    .bind(TxStatus::Queued.as_str())
+    .bind(TxStatus::Broadcasting.as_str())
+    .bind(TxStatus::RetryScheduled.as_str())
+    .bind(TxStatus::Paused.as_str())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+pub async fn lease_due_webhook_deliveries(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+    lease_owner: &str,
+    lease_until: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<WebhookDeliveryJob>> {
+    let rows = sqlx::query_as::<_, WebhookDeliveryJob>(
+        r#"
+        WITH due AS (
+            SELECT id
+            FROM webhook_deliveries
+            WHERE status = $1
+              AND next_attempt_at <= $2
+              AND (lease_until IS NULL OR lease_until < $2)
+            ORDER BY next_attempt_at ASC
+            LIMIT $3
+            FOR UPDATE SKIP LOCKED
+        ),
+        leased AS (
+            UPDATE webhook_deliveries
+            SET lease_owner = $4,
+                lease_until = $5,
+                updated_at = NOW()
+            WHERE id IN (SELECT id FROM due)
+            RETURNING id, webhook_id, event_id, payload, attempts
+        )
+        SELECT l.id, l.event_id, l.payload, l.attempts, w.url, w.secret
+        FROM leased l
+        JOIN webhooks w ON w.id = l.webhook_id
+        "#,
+    )
+    .bind(WebhookDeliveryStatus::Pending.as_str())
+    .bind(now)
+    .bind(limit)
+    .bind(lease_owner)
+    .bind(lease_until)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn mark_webhook_delivered(
+    pool: &PgPool,
+    id: i64,
+    lease_owner: &str,
+    attempts: i32,
+    status_code: i32,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = $1,
+            attempts = $2,
+            last_status_code = $3,
+            last_error = NULL,
+            next_attempt_at = NULL,
+            delivered_at = NOW(),
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE id = $4
+          AND lease_owner = $5
+        "#,
+    )
+    .bind(WebhookDeliveryStatus::Delivered.as_str())
+    .bind(attempts)
+    .bind(status_code)
+    .bind(id)
+    .bind(lease_owner)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn reschedule_webhook_delivery(
+    pool: &PgPool,
+    id: i64,
+    lease_owner: &str,
+    status: WebhookDeliveryStatus,
+    next_attempt_at: Option<DateTime<Utc>>,
+    attempts: i32,
+    last_error: &str,
+    status_code: Option<i32>,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = $1,
+            next_attempt_at = $2,
+            attempts = $3,
+            last_error = $4,
+            last_status_code = $5,
+            lease_owner = NULL,
+            lease_until = NULL,
+            updated_at = NOW()
+        WHERE id = $6
+          AND lease_owner = $7
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(next_attempt_at)
+    .bind(attempts)
+    .bind(last_error)
+    .bind(status_code)
+    .bind(id)
+    .bind(lease_owner)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
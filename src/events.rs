@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use redis::AsyncCommands;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+use tracing::{info, warn};
+
+use crate::models::{TxEvent, TxRecord};
+use crate::state::AppState;
+
+pub const EVENTS_CHANNEL: &str = "watchtower:events";
+
+const LOCAL_BUFFER: usize = 1024;
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// In-process fan-out of transaction events to the SSE and WebSocket streams served by this
+/// replica. Events reach it through the Redis channel, or Postgres `NOTIFY` when running without
+/// Redis, so every replica sees every change.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<TxEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(LOCAL_BUFFER);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TxEvent> {
+        self.sender.subscribe()
+    }
+
+    fn send_local(&self, event: TxEvent) {
+        // No receivers just means nobody is streaming right now.
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Subscription filter shared by the SSE and WebSocket endpoints. Hex values are expected in
+/// lowercase `0x` form, as emitted in events.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub chain_id: Option<u64>,
+    pub sender: Option<String>,
+    pub group_id: Option<String>,
+    pub tx_hash: Option<String>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &TxEvent) -> bool {
+        if let Some(chain_id) = self.chain_id
+            && chain_id != event.chain_id
+        {
+            return false;
+        }
+        if let Some(sender) = &self.sender
+            && *sender != event.sender
+        {
+            return false;
+        }
+        if let Some(group_id) = &self.group_id
+            && Some(group_id) != event.group_id.as_ref()
+        {
+            return false;
+        }
+        if let Some(tx_hash) = &self.tx_hash
+            && *tx_hash != event.tx_hash
+        {
+            return false;
+        }
+        true
+    }
+}
+
+pub fn start(state: AppState) {
+    tokio::spawn(async move {
+        run_subscriber(state).await;
+    });
+}
+
+/// Publishes the current state of `record` to every replica. Falls back to local subscribers
+/// when the channel is unavailable so this replica's streams keep working.
+pub async fn publish(state: &AppState, record: &TxRecord) {
+    let event = TxEvent::from_record(record);
+    let payload = match serde_json::to_string(&event) {
+        Ok(payload) => payload,
+        Err(err) => {
+            warn!(error = %err, "failed to encode tx event");
+            return;
+        }
+    };
+
+    let published = match state.redis.clone() {
+        Some(mut redis) => redis
+            .publish::<_, _, ()>(EVENTS_CHANNEL, payload)
+            .await
+            .map_err(anyhow::Error::from),
+        None => sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(EVENTS_CHANNEL)
+            .bind(payload)
+            .execute(&state.db)
+            .await
+            .map(|_| ())
+            .map_err(anyhow::Error::from),
+    };
+    if let Err(err) = published {
+        warn!(error = %err, "failed to publish tx event");
+        state.events.send_local(event);
+    }
+}
+
+async fn run_subscriber(state: AppState) {
+    loop {
+        if let Err(err) = subscribe(&state).await {
+            warn!(error = %err, "tx event subscription failed");
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+async fn subscribe(state: &AppState) -> anyhow::Result<()> {
+    // Decide the mode the same way `publish` does. The Redis section is restart-only, so its URL
+    // is the one `state.redis` was opened with.
+    if state.redis.is_none() {
+        return listen(state).await;
+    }
+    let url = state
+        .config
+        .current()
+        .redis
+        .as_ref()
+        .map(|redis| redis.url.clone())
+        .ok_or_else(|| anyhow::anyhow!("redis connection has no configured url"))?;
+    let client = redis::Client::open(url.as_str())?;
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(EVENTS_CHANNEL).await?;
+    info!(channel = EVENTS_CHANNEL, "subscribed to tx events");
+
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+        let payload: String = match message.get_payload() {
+            Ok(payload) => payload,
+            Err(err) => {
+                warn!(error = %err, "invalid tx event payload");
+                continue;
+            }
+        };
+        receive(state, &payload);
+    }
+
+    Err(anyhow::anyhow!("tx event subscription ended"))
+}
+
+async fn listen(state: &AppState) -> anyhow::Result<()> {
+    let mut listener = PgListener::connect_with(&state.db).await?;
+    listener.listen(EVENTS_CHANNEL).await?;
+    info!(channel = EVENTS_CHANNEL, "listening for tx events");
+
+    loop {
+        let notification = listener.recv().await?;
+        receive(state, notification.payload());
+    }
+}
+
+fn receive(state: &AppState, payload: &str) {
+    match serde_json::from_str::<TxEvent>(payload) {
+        Ok(event) => state.events.send_local(event),
+        Err(err) => warn!(error = %err, "failed to decode tx event"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::EventFilter;
+    use crate::models::TxEvent;
+
+    fn event() -> TxEvent {
+        TxEvent {
+            id: Uuid::new_v4(),
+            event_type: "transaction.broadcasting".to_string(),
+            created_at: 0,
+            chain_id: 42431,
+            tx_hash: format!("0x{}", "ab".repeat(32)),
+            sender: format!("0x{}", "11".repeat(20)),
+            group_id: Some(format!("0x{}", "22".repeat(16))),
+            nonce_key: "0x0".to_string(),
+            nonce: 1,
+            status: "broadcasting".to_string(),
+            attempts: 1,
+            last_error: None,
+            replaced_by: None,
+            reverted: None,
+        }
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        assert!(EventFilter::default().matches(&event()));
+    }
+
+    #[test]
+    fn filter_requires_every_field_to_match() {
+        let event = event();
+        let filter = EventFilter {
+            chain_id: Some(42431),
+            sender: Some(event.sender.clone()),
+            group_id: event.group_id.clone(),
+            tx_hash: None,
+        };
+        assert!(filter.matches(&event));
+
+        let other_chain = EventFilter {
+            chain_id: Some(1),
+            ..filter.clone()
+        };
+        assert!(!other_chain.matches(&event));
+
+        let other_group = EventFilter {
+            group_id: Some(format!("0x{}", "33".repeat(16))),
+            ..filter
+        };
+        assert!(!other_group.matches(&event));
+
+        let ungrouped = TxEvent {
+            group_id: None,
+            ..event.clone()
+        };
+        let group_filter = EventFilter {
+            group_id: event.group_id.clone(),
+            ..EventFilter::default()
+        };
+        assert!(!group_filter.matches(&ungrouped));
+    }
+}
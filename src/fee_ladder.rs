@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use tempo_alloy::rpc::TempoTransactionReceipt;
+use tracing::info;
+
+use crate::db;
+use crate::events;
+use crate::models::{FeeLadderRecord, TxRecord};
+use crate::rpc::{ChainRpc, fetch_receipt};
+use crate::scheduler;
+use crate::state::AppState;
+
+/// Looks for a receipt for `record`. Transactions with the same nonce that were replaced after
+/// they were broadcast are checked too, since an escalated fee ladder rung or a replaced
+/// transaction can still be mined instead.
+pub async fn find_receipt(
+    state: &AppState,
+    chain: &ChainRpc,
+    record: &TxRecord,
+) -> anyhow::Result<Option<(TxRecord, TempoTransactionReceipt)>> {
+    if let Some(receipt) = fetch_receipt(chain, record).await? {
+        return Ok(Some((record.clone(), receipt)));
+    }
+
+    for replaced in db::list_broadcast_replaced_at_nonce(&state.db, record).await? {
+        if let Some(receipt) = fetch_receipt(chain, &replaced).await? {
+            return Ok(Some((replaced, receipt)));
+        }
+    }
+    Ok(None)
+}
+
+/// Retires what the mined `winner` made obsolete: the other rungs of its ladder and, when
+/// `winner` had been replaced, the pending transactions that replaced it.
+pub async fn retire_siblings(
+    state: &AppState,
+    winner: &TxRecord,
+    was_replaced: bool,
+) -> anyhow::Result<()> {
+    let mut retired = db::retire_fee_ladder(&state.db, winner).await?;
+    if was_replaced {
+        retired.extend(db::retire_replacements(&state.db, winner).await?);
+    }
+    if retired.is_empty() {
+        return Ok(());
+    }
+
+    let chain_id = winner.chain_id.to_uint();
+    let _ = scheduler::unschedule_records(state, &retired).await;
+    for record in &retired {
+        events::publish(state, record).await;
+    }
+    info!(
+        %chain_id,
+        tx_hash = %bytes_to_hex(&winner.tx_hash),
+        retired = retired.len(),
+        "nonce settled"
+    );
+    Ok(())
+}
+
+/// Moves the ladder to its next rung when the leased `record` has used up its attempts or time.
+/// Returns true when `record` was replaced and must not be broadcast.
+pub async fn escalate_if_due(
+    state: &AppState,
+    record: &TxRecord,
+    lease_owner: &str,
+    now: DateTime<Utc>,
+) -> anyhow::Result<bool> {
+    let Some(ladder_id) = record.ladder_id else {
+        return Ok(false);
+    };
+    let Some(ladder) = db::get_fee_ladder(&state.db, ladder_id).await? else {
+        return Ok(false);
+    };
+    if !escalation_due(&ladder, record.attempts, record.eligible_at, now) {
+        return Ok(false);
+    }
+
+    let Some((replaced, promoted)) =
+        db::escalate_fee_ladder(&state.db, record, lease_owner).await?
+    else {
+        return Ok(false);
+    };
+
+    scheduler::schedule_records(state, std::slice::from_ref(&promoted)).await?;
+    info!(
+        chain_id = %record.chain_id.to_uint(),
+        tx_hash = %bytes_to_hex(&replaced.tx_hash),
+        replaced_by = %bytes_to_hex(&promoted.tx_hash),
+        rung = ?promoted.ladder_rung,
+        attempts = record.attempts,
+        "fee ladder escalated"
+    );
+    events::publish(state, &replaced).await;
+    events::publish(state, &promoted).await;
+    Ok(true)
+}
+
+/// A rung escalates after `escalate_after_attempts` broadcasts or `escalate_after_seconds` since
+/// it became eligible, whichever comes first.
+fn escalation_due(
+    ladder: &FeeLadderRecord,
+    attempts: i32,
+    active_since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> bool {
+    attempts >= ladder.escalate_after_attempts
+        || now - active_since >= chrono::Duration::seconds(ladder.escalate_after_seconds)
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx_pg_uint::PgU64;
+    use uuid::Uuid;
+
+    use super::escalation_due;
+    use crate::models::FeeLadderRecord;
+
+    #[test]
+    fn escalation_due_after_attempts_or_time() {
+        let now = Utc::now();
+        let ladder = FeeLadderRecord {
+            id: Uuid::new_v4(),
+            chain_id: PgU64::from(42431),
+            escalate_after_attempts: 3,
+            escalate_after_seconds: 60,
+            created_at: now,
+        };
+
+        assert!(!escalation_due(&ladder, 0, now, now));
+        assert!(!escalation_due(
+            &ladder,
+            2,
+            now - Duration::seconds(59),
+            now
+        ));
+        assert!(escalation_due(&ladder, 3, now, now));
+        assert!(escalation_due(&ladder, 1, now - Duration::seconds(60), now));
+    }
+}
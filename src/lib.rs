@@ -1,12 +1,21 @@
 pub mod api;
+pub mod api_keys;
+pub mod auth;
 pub mod broadcaster;
 pub mod config;
 pub mod db;
+pub mod events;
+pub mod fee_ladder;
 pub mod models;
 pub mod nonce_key;
+pub mod reload;
+pub mod retention;
 pub mod rpc;
 pub mod scheduler;
 pub mod serde_helpers;
+pub mod shutdown;
 pub mod state;
+pub mod telemetry;
 pub mod tx;
 pub mod watcher;
+pub mod webhooks;
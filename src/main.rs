@@ -1,20 +1,31 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::path::PathBuf;
 use std::process;
 use std::sync::Arc;
 
 use anyhow::Result;
 use axum::Router;
-use chrono::Utc;
-use clap::{CommandFactory, Parser};
+use chrono::{DateTime, Utc};
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::trace::TraceLayer;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use uuid::Uuid;
 
-use temprano_watchtower::config::Config;
+use temprano_watchtower::config::{Config, ConfigHandle};
+use temprano_watchtower::db::{PageCursor, SortKey, SortOrder, TxFilters};
+use temprano_watchtower::events::EventBus;
+use temprano_watchtower::models::{NewApiKey, TxRecord, TxStatus};
 use temprano_watchtower::rpc::RpcManager;
+use temprano_watchtower::scheduler::Wakeups;
+use temprano_watchtower::shutdown::{self, Shutdown};
 use temprano_watchtower::state::AppState;
-use temprano_watchtower::{api, db, scheduler, watcher};
+use temprano_watchtower::{
+    api, api_keys, db, events, reload, retention, scheduler, telemetry, webhooks,
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "temprano-watchtower", version)]
@@ -23,9 +34,96 @@ struct Cli {
         long,
         default_value = "config.toml",
         env = "CONFIG_PATH",
-        value_name = "PATH"
+        value_name = "PATH",
+        global = true
     )]
     config: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the service. This is the default.
+    Serve,
+    /// Apply database migrations and exit.
+    Migrate,
+    /// Manage API keys.
+    #[command(subcommand)]
+    ApiKey(ApiKeyCommand),
+    /// Make a transaction due for broadcast now, clearing its lease and last error.
+    Requeue(TxArgs),
+    /// Cancel every transaction in a group, like the cancel endpoint but without a signature.
+    CancelGroup {
+        #[arg(long)]
+        sender: String,
+        #[arg(long)]
+        group_id: String,
+    },
+    /// Rebuild the Redis schedule of every configured chain from the database.
+    RebuildRedis,
+    /// Show a transaction with its lease and broadcast attempts.
+    Inspect(TxArgs),
+    /// Count transactions by chain and status.
+    Stats,
+    /// Write transactions as JSON Lines, in the same shape as the API returns them.
+    Export(ExportArgs),
+}
+
+#[derive(Debug, Args)]
+struct TxArgs {
+    tx_hash: String,
+    /// Needed only when the same hash was submitted on several chains.
+    #[arg(long)]
+    chain_id: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+struct ExportArgs {
+    #[arg(long)]
+    chain_id: Option<u64>,
+    #[arg(long)]
+    sender: Option<String>,
+    /// Only export this status. Repeat for several statuses.
+    #[arg(long = "status", value_name = "STATUS")]
+    statuses: Vec<String>,
+    /// RFC 3339 timestamp, inclusive.
+    #[arg(long)]
+    created_after: Option<DateTime<Utc>>,
+    /// RFC 3339 timestamp, exclusive.
+    #[arg(long)]
+    created_before: Option<DateTime<Utc>>,
+    /// Write to this file instead of stdout.
+    #[arg(long, short, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+enum ApiKeyCommand {
+    /// Create a key. The key itself is printed once and only its hash is stored.
+    Create(CreateApiKeyArgs),
+    /// List keys with their quotas and restrictions.
+    List,
+    /// Revoke a key by id.
+    Revoke { id: Uuid },
+}
+
+#[derive(Debug, Args)]
+struct CreateApiKeyArgs {
+    #[arg(long)]
+    name: String,
+    #[arg(long, default_value_t = 600)]
+    requests_per_minute: i32,
+    #[arg(long, default_value_t = 10_000)]
+    txs_per_day: i32,
+    #[arg(long, default_value_t = 100)]
+    max_queued_per_sender: i32,
+    /// Only allow submissions to this chain. Repeat for several chains.
+    #[arg(long = "chain-id", value_name = "CHAIN_ID")]
+    chain_ids: Vec<u64>,
+    /// Only allow submissions for this sender address. Repeat for several senders.
+    #[arg(long = "sender", value_name = "ADDRESS")]
+    senders: Vec<String>,
 }
 
 #[tokio::main]
@@ -39,32 +137,75 @@ async fn main() -> Result<()> {
         process::exit(1);
     }
 
+    // Admin commands print their results to stdout, so their logs go to stderr.
+    let writer = if matches!(cli.command, None | Some(Command::Serve)) {
+        BoxMakeWriter::new(io::stdout)
+    } else {
+        BoxMakeWriter::new(io::stderr)
+    };
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
     tracing_subscriber::fmt()
         .with_env_filter(filter)
         .with_target(false)
+        .with_writer(writer)
         .init();
+    telemetry::init();
 
-    let config = Arc::new(Config::load_from_path(cli.config)?);
+    let config = Config::load_from_path(&cli.config)?;
     let db = db::connect(&config.database.url).await?;
     db::migrate(&db).await?;
 
-    let redis = redis::Client::open(config.redis.url.as_str())?;
-    let redis = redis::aio::ConnectionManager::new(redis).await?;
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(cli.config, config, db).await,
+        Command::Migrate => {
+            println!("migrations applied");
+            Ok(())
+        }
+        Command::ApiKey(command) => run_api_key_command(&db, command).await,
+        Command::Requeue(args) => requeue(&build_state(config, db).await?, args).await,
+        Command::CancelGroup { sender, group_id } => {
+            let state = build_state(config, db).await?;
+            cancel_group(&state, &sender, &group_id).await
+        }
+        Command::RebuildRedis => rebuild_redis(&build_state(config, db).await?).await,
+        Command::Inspect(args) => inspect(&db, args).await,
+        Command::Stats => stats(&db).await,
+        Command::Export(args) => export(&db, args).await,
+    }
+}
+
+async fn build_state(config: Config, db: sqlx::PgPool) -> Result<AppState> {
+    let redis = match &config.redis {
+        Some(redis) => {
+            let client = redis::Client::open(redis.url.as_str())?;
+            Some(redis::aio::ConnectionManager::new(client).await?)
+        }
+        None => None,
+    };
 
     let rpcs = Arc::new(RpcManager::new(&config).await?);
 
-    let state = AppState {
-        config: config.clone(),
+    Ok(AppState {
+        config: ConfigHandle::new(config),
         db,
         redis,
         rpcs,
+        events: EventBus::new(),
+        wakeups: Wakeups::new(),
+        shutdown: Shutdown::new(),
         started_at: Utc::now(),
-    };
+    })
+}
+
+async fn serve(config_path: PathBuf, config: Config, db: sqlx::PgPool) -> Result<()> {
+    let state = build_state(config.clone(), db).await?;
 
     scheduler::recover_after_restart(&state).await?;
-    scheduler::start(state.clone());
-    watcher::start(state.clone());
+    scheduler::start_redis_reconciler(state.clone());
+    reload::start(state.clone(), config_path);
+    webhooks::start(state.clone());
+    events::start(state.clone());
+    retention::start(state.clone());
 
     let app = Router::new()
         .merge(api::router(state.clone()))
@@ -73,7 +214,284 @@ async fn main() -> Result<()> {
 
     let listener = tokio::net::TcpListener::bind(&config.server.bind).await?;
     info!(bind = %config.server.bind, "listening");
-    axum::serve(listener, app).await?;
 
+    let signal_shutdown = state.shutdown.clone();
+    tokio::spawn(async move {
+        shutdown::signal_received().await;
+        info!("shutdown signal received");
+        signal_shutdown.trigger();
+    });
+
+    let serve_shutdown = state.shutdown.clone();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move { serve_shutdown.triggered().await })
+        .await?;
+    shutdown::drain(&state).await;
+
+    Ok(())
+}
+
+async fn run_api_key_command(db: &sqlx::PgPool, command: ApiKeyCommand) -> Result<()> {
+    match command {
+        ApiKeyCommand::Create(args) => {
+            if args.requests_per_minute <= 0 {
+                anyhow::bail!("--requests-per-minute must be positive");
+            }
+            let chain_ids = args
+                .chain_ids
+                .iter()
+                .map(|chain_id| i64::try_from(*chain_id))
+                .collect::<Result<Vec<_>, _>>()?;
+            let senders = args
+                .senders
+                .iter()
+                .map(|sender| parse_sender(sender))
+                .collect::<Result<Vec<_>>>()?;
+
+            let key = api_keys::generate_key();
+            let record = db::insert_api_key(
+                db,
+                &NewApiKey {
+                    name: args.name,
+                    key_hash: api_keys::hash_key(&key),
+                    requests_per_minute: args.requests_per_minute,
+                    txs_per_day: args.txs_per_day,
+                    max_queued_per_sender: args.max_queued_per_sender,
+                    chain_ids: (!chain_ids.is_empty()).then_some(chain_ids),
+                    senders: (!senders.is_empty()).then_some(senders),
+                },
+            )
+            .await?;
+            println!("id:  {}", record.id);
+            println!("key: {key}");
+        }
+        ApiKeyCommand::List => {
+            for record in db::list_api_keys(db).await? {
+                let chains = record
+                    .chain_ids
+                    .map(|ids| format!("{ids:?}"))
+                    .unwrap_or_else(|| "any".to_string());
+                let senders = record
+                    .senders
+                    .map(|senders| {
+                        senders
+                            .iter()
+                            .map(|sender| format!("0x{}", hex::encode(sender)))
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    })
+                    .unwrap_or_else(|| "any".to_string());
+                let revoked = if record.revoked_at.is_some() {
+                    " revoked"
+                } else {
+                    ""
+                };
+                println!(
+                    "{} {} rpm={} txs/day={} max_queued={} chains={} senders={}{}",
+                    record.id,
+                    record.name,
+                    record.requests_per_minute,
+                    record.txs_per_day,
+                    record.max_queued_per_sender,
+                    chains,
+                    senders,
+                    revoked
+                );
+            }
+        }
+        ApiKeyCommand::Revoke { id } => {
+            if !db::revoke_api_key(db, id).await? {
+                anyhow::bail!("no active api key with id {id}");
+            }
+            println!("revoked {id}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn find_tx(db: &sqlx::PgPool, args: &TxArgs) -> Result<TxRecord> {
+    let tx_hash = parse_fixed_hex(&args.tx_hash, 32, "transaction hash")?;
+    db::get_tx_by_hash(db, args.chain_id, &tx_hash)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("transaction {} not found", args.tx_hash))
+}
+
+async fn requeue(state: &AppState, args: TxArgs) -> Result<()> {
+    let record = find_tx(&state.db, &args).await?;
+    let Some(requeued) = db::requeue_tx(&state.db, record.id).await? else {
+        anyhow::bail!(
+            "transaction {} is {} and cannot be requeued; only unexpired invalid, \
+             retry_scheduled or unleased broadcasting transactions that still have their signed \
+             transaction can be",
+            args.tx_hash,
+            record.status
+        );
+    };
+    scheduler::schedule_records(state, std::slice::from_ref(&requeued)).await?;
+    events::publish(state, &requeued).await;
+    println!("requeued {} (was {})", args.tx_hash, record.status);
+    Ok(())
+}
+
+async fn cancel_group(state: &AppState, sender: &str, group_id: &str) -> Result<()> {
+    let sender = parse_sender(sender)?;
+    let group_id = parse_fixed_hex(group_id, 16, "group id")?;
+    let records = db::cancel_group(&state.db, &sender, &group_id, None, 0..=u64::MAX).await?;
+    if records.is_empty() {
+        anyhow::bail!("no cancelable transactions in group");
+    }
+
+    scheduler::unschedule_records(state, &records).await?;
+    for record in &records {
+        events::publish(state, record).await;
+        println!("canceled 0x{}", hex::encode(&record.tx_hash));
+    }
+    Ok(())
+}
+
+async fn rebuild_redis(state: &AppState) -> Result<()> {
+    let mut chain_ids: Vec<u64> = state.config.current().rpc.chains.keys().copied().collect();
+    chain_ids.sort_unstable();
+    for chain_id in chain_ids {
+        let scheduled = scheduler::rebuild_queues(state, chain_id).await?;
+        println!("chain {chain_id}: scheduled {scheduled}");
+    }
+    Ok(())
+}
+
+async fn inspect(db: &sqlx::PgPool, args: TxArgs) -> Result<()> {
+    let record = find_tx(db, &args).await?;
+    let lease = match (&record.lease_owner, record.lease_until) {
+        (Some(owner), Some(until)) => format!("{owner} until {}", until.to_rfc3339()),
+        _ => "none".to_string(),
+    };
+
+    println!("id:             {}", record.id);
+    println!("chain id:       {}", record.chain_id.to_uint());
+    println!("tx hash:        0x{}", hex::encode(&record.tx_hash));
+    println!("sender:         0x{}", hex::encode(&record.sender));
+    println!("nonce key:      0x{}", hex::encode(&record.nonce_key));
+    println!("nonce:          {}", record.nonce.to_uint());
+    if let Some(group_id) = &record.group_id {
+        println!("group id:       0x{}", hex::encode(group_id));
+    }
+    println!("status:         {}", record.status);
+    println!("attempts:       {}", record.attempts);
+    if let Some(last_error) = &record.last_error {
+        println!("last error:     {last_error}");
+    }
+    println!("eligible at:    {}", record.eligible_at.to_rfc3339());
+    if let Some(expires_at) = record.expires_at {
+        println!("expires at:     {}", expires_at.to_rfc3339());
+    }
+    if let Some(next_action_at) = record.next_action_at {
+        println!("next action at: {}", next_action_at.to_rfc3339());
+    }
+    println!("lease:          {lease}");
+    println!("raw tx stored:  {}", record.raw_tx.is_some());
+    println!("updated at:     {}", record.updated_at.to_rfc3339());
+
+    let attempts = db::list_broadcast_attempts(db, record.id).await?;
+    if !attempts.is_empty() {
+        println!("broadcast attempts:");
+    }
+    for attempt in attempts {
+        let error = attempt
+            .error
+            .map(|error| format!(": {error}"))
+            .unwrap_or_default();
+        println!(
+            "  #{} {} {} {}ms {}{}",
+            attempt.attempt,
+            attempt.created_at.to_rfc3339(),
+            attempt.endpoint,
+            attempt.latency_ms,
+            attempt.outcome,
+            error
+        );
+    }
+    Ok(())
+}
+
+async fn stats(db: &sqlx::PgPool) -> Result<()> {
+    let mut counts = db::count_txs_by_status(db).await?;
+    counts
+        .sort_by(|a, b| (a.chain_id.to_uint(), &a.status).cmp(&(b.chain_id.to_uint(), &b.status)));
+    for count in counts {
+        println!(
+            "{} {} {}",
+            count.chain_id.to_uint(),
+            count.status,
+            count.count
+        );
+    }
+    Ok(())
+}
+
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+async fn export(db: &sqlx::PgPool, args: ExportArgs) -> Result<()> {
+    let statuses = args
+        .statuses
+        .iter()
+        .map(|status| {
+            TxStatus::try_from(status.as_str())
+                .map_err(|_| anyhow::anyhow!("unknown status {status}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut filters = TxFilters {
+        chain_id: args.chain_id,
+        sender: args.sender.as_deref().map(parse_sender).transpose()?,
+        statuses,
+        created_after: args.created_after,
+        created_before: args.created_before,
+        sort: SortKey::CreatedAt,
+        order: SortOrder::Asc,
+        limit: EXPORT_PAGE_SIZE,
+        ..TxFilters::default()
+    };
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let mut exported = 0;
+    loop {
+        let mut records = db::list_txs(db, filters.clone()).await?;
+        let has_more = records.len() as i64 > EXPORT_PAGE_SIZE;
+        records.truncate(EXPORT_PAGE_SIZE as usize);
+        for record in &records {
+            serde_json::to_writer(&mut out, &api::tx_json(record)?)?;
+            out.write_all(b"\n")?;
+        }
+        exported += records.len();
+
+        match records.last() {
+            Some(last) if has_more => {
+                filters.after = Some(PageCursor {
+                    sort: filters.sort,
+                    order: filters.order,
+                    value: filters.sort.value_of(last),
+                    id: last.id,
+                });
+            }
+            _ => break,
+        }
+    }
+    out.flush()?;
+    eprintln!("exported {exported} transactions");
     Ok(())
 }
+
+fn parse_sender(value: &str) -> Result<Vec<u8>> {
+    parse_fixed_hex(value, 20, "sender address")
+}
+
+fn parse_fixed_hex(value: &str, len: usize, what: &str) -> Result<Vec<u8>> {
+    let bytes = hex::decode(value.trim_start_matches("0x"))?;
+    if bytes.len() != len {
+        anyhow::bail!("invalid {what} {value}");
+    }
+    Ok(bytes)
+}
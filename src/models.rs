@@ -1,7 +1,9 @@
+use alloy::primitives::U256;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use sqlx_pg_uint::PgU64;
+use sqlx_pg_uint::{PgU64, PgU128};
+use uuid::Uuid;
 
 #[derive(Debug, Clone, FromRow)]
 pub struct TxRecord {
@@ -32,6 +34,19 @@ pub struct TxRecord {
     pub created_at: DateTime<Utc>,
     #[allow(dead_code)]
     pub updated_at: DateTime<Utc>,
+    pub max_fee_per_gas: Option<PgU128>,
+    /// Hash of the transaction that superseded this one when `status` is `replaced`.
+    pub replaced_by: Option<Vec<u8>>,
+    pub ladder_id: Option<Uuid>,
+    /// Position in the fee ladder, cheapest first.
+    pub ladder_rung: Option<i32>,
+    pub receipt_block_number: Option<PgU64>,
+    pub receipt_block_hash: Option<Vec<u8>>,
+    /// Receipt status; `false` means the transaction was mined but reverted.
+    pub receipt_success: Option<bool>,
+    pub receipt_gas_used: Option<PgU64>,
+    pub receipt_effective_gas_price: Option<PgU128>,
+    pub receipt_fee_token: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +65,36 @@ pub struct NewTx {
     pub status: String,
     pub group_id: Option<Vec<u8>>,
     pub next_action_at: DateTime<Utc>,
+    pub max_fee_per_gas: PgU128,
+    pub ladder_id: Option<Uuid>,
+    pub ladder_rung: Option<i32>,
+    /// Addresses the transaction calls, indexed in `tx_call_targets`.
+    pub call_targets: Vec<Vec<u8>>,
+}
+
+/// Block a receipt was included in.
+#[derive(Debug, Clone)]
+pub struct ReceiptBlock {
+    pub number: u64,
+    pub hash: Vec<u8>,
+}
+
+/// Receipt of a mined transaction, with the fields stored in their own columns.
+#[derive(Debug, Clone)]
+pub struct ReceiptOutcome {
+    pub receipt: serde_json::Value,
+    pub block: Option<ReceiptBlock>,
+    pub success: bool,
+    pub gas_used: u64,
+    pub effective_gas_price: u128,
+    pub fee_token: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct TxStatusCount {
+    pub chain_id: PgU64,
+    pub status: String,
+    pub count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,10 +104,18 @@ pub enum TxStatus {
     Broadcasting,
     RetryScheduled,
     Executed,
+    /// Executed and at least `watcher.confirmations` blocks deep.
+    Confirmed,
     Expired,
     Invalid,
     StaleByNonce,
     CanceledLocally,
+    Replaced,
+    /// Higher fee ladder rung waiting for the rungs below it to escalate.
+    Standby,
+    /// Held back by the sender until the group is resumed. Still watched for expiry and nonce
+    /// staleness.
+    Paused,
 }
 
 impl TxStatus {
@@ -72,10 +125,14 @@ impl TxStatus {
             TxStatus::Broadcasting => "broadcasting",
             TxStatus::RetryScheduled => "retry_scheduled",
             TxStatus::Executed => "executed",
+            TxStatus::Confirmed => "confirmed",
             TxStatus::Expired => "expired",
             TxStatus::Invalid => "invalid",
             TxStatus::StaleByNonce => "stale_by_nonce",
             TxStatus::CanceledLocally => "canceled_locally",
+            TxStatus::Replaced => "replaced",
+            TxStatus::Standby => "standby",
+            TxStatus::Paused => "paused",
         }
     }
 }
@@ -95,11 +152,215 @@ impl TryFrom<&str> for TxStatus {
             "broadcasting" => Ok(TxStatus::Broadcasting),
             "retry_scheduled" => Ok(TxStatus::RetryScheduled),
             "executed" => Ok(TxStatus::Executed),
+            "confirmed" => Ok(TxStatus::Confirmed),
             "expired" => Ok(TxStatus::Expired),
             "invalid" => Ok(TxStatus::Invalid),
             "stale_by_nonce" => Ok(TxStatus::StaleByNonce),
             "canceled_locally" => Ok(TxStatus::CanceledLocally),
+            "replaced" => Ok(TxStatus::Replaced),
+            "standby" => Ok(TxStatus::Standby),
+            "paused" => Ok(TxStatus::Paused),
             _ => Err(()),
         }
     }
 }
+
+/// Status change notification for a single transaction, as delivered to webhooks and event
+/// streams.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxEvent {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub created_at: i64,
+    pub chain_id: u64,
+    pub tx_hash: String,
+    pub sender: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    pub nonce_key: String,
+    pub nonce: u64,
+    pub status: String,
+    pub attempts: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverted: Option<bool>,
+}
+
+impl TxEvent {
+    pub fn from_record(record: &TxRecord) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event_type: format!("transaction.{}", record.status),
+            created_at: Utc::now().timestamp(),
+            chain_id: record.chain_id.to_uint(),
+            tx_hash: format!("0x{}", hex::encode(&record.tx_hash)),
+            sender: format!("0x{}", hex::encode(&record.sender)),
+            group_id: record
+                .group_id
+                .as_ref()
+                .map(|value| format!("0x{}", hex::encode(value))),
+            nonce_key: format!("{:#x}", U256::from_be_slice(&record.nonce_key)),
+            nonce: record.nonce.to_uint(),
+            status: record.status.clone(),
+            attempts: record.attempts,
+            last_error: record.last_error.clone(),
+            replaced_by: record
+                .replaced_by
+                .as_ref()
+                .map(|value| format!("0x{}", hex::encode(value))),
+            reverted: record.receipt_success.map(|success| !success),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookRecord {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub chain_id: PgU64,
+    pub sender: Option<Vec<u8>>,
+    pub group_id: Option<Vec<u8>>,
+    pub tx_hash: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWebhook {
+    pub url: String,
+    pub secret: String,
+    pub chain_id: PgU64,
+    pub sender: Option<Vec<u8>>,
+    pub group_id: Option<Vec<u8>>,
+    pub tx_hash: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiKeyRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub key_hash: Vec<u8>,
+    pub requests_per_minute: i32,
+    pub txs_per_day: i32,
+    pub max_queued_per_sender: i32,
+    /// Chains the key may submit to; `None` allows every configured chain.
+    pub chain_ids: Option<Vec<i64>>,
+    /// Senders the key may submit for; `None` allows any sender.
+    pub senders: Option<Vec<Vec<u8>>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyRecord {
+    pub fn allows_chain(&self, chain_id: u64) -> bool {
+        self.chain_ids.as_ref().is_none_or(|chain_ids| {
+            chain_ids
+                .iter()
+                .any(|allowed| u64::try_from(*allowed) == Ok(chain_id))
+        })
+    }
+
+    pub fn allows_sender(&self, sender: &[u8]) -> bool {
+        self.senders
+            .as_ref()
+            .is_none_or(|senders| senders.iter().any(|allowed| allowed == sender))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub name: String,
+    pub key_hash: Vec<u8>,
+    pub requests_per_minute: i32,
+    pub txs_per_day: i32,
+    pub max_queued_per_sender: i32,
+    pub chain_ids: Option<Vec<i64>>,
+    pub senders: Option<Vec<Vec<u8>>>,
+}
+
+/// Escalation policy shared by the rungs of a fee ladder.
+#[derive(Debug, Clone, FromRow)]
+pub struct FeeLadderRecord {
+    pub id: Uuid,
+    pub chain_id: PgU64,
+    pub escalate_after_attempts: i32,
+    pub escalate_after_seconds: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A leased outbox row joined with the destination it should be delivered to.
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookDeliveryJob {
+    pub id: i64,
+    pub event_id: Uuid,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct BroadcastAttemptRecord {
+    pub id: i64,
+    pub tx_id: i64,
+    pub attempt: i32,
+    pub endpoint: String,
+    pub outcome: String,
+    pub error: Option<String>,
+    pub latency_ms: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Result of submitting a transaction to a single RPC endpoint.
+#[derive(Debug, Clone)]
+pub struct NewBroadcastAttempt {
+    pub tx_id: i64,
+    pub attempt: i32,
+    pub endpoint: String,
+    pub outcome: BroadcastAttemptOutcome,
+    pub error: Option<String>,
+    pub latency_ms: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastAttemptOutcome {
+    Accepted,
+    AlreadyKnown,
+    Invalid,
+    Retry,
+    Timeout,
+}
+
+impl BroadcastAttemptOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BroadcastAttemptOutcome::Accepted => "accepted",
+            BroadcastAttemptOutcome::AlreadyKnown => "already_known",
+            BroadcastAttemptOutcome::Invalid => "invalid",
+            BroadcastAttemptOutcome::Retry => "retry",
+            BroadcastAttemptOutcome::Timeout => "timeout",
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tokio::signal::unix::{SignalKind, signal};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::config::{Config, WebhookConfig};
+use crate::state::AppState;
+use crate::{scheduler, watcher};
+
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Per-chain background tasks that are started, stopped and restarted on reload.
+struct ChainTasks {
+    scheduler: CancellationToken,
+    watcher: CancellationToken,
+}
+
+impl ChainTasks {
+    fn spawn(state: &AppState, chain_id: u64) -> Self {
+        let tasks = Self {
+            scheduler: state.shutdown.child_token(),
+            watcher: state.shutdown.child_token(),
+        };
+        scheduler::spawn_chain(state.clone(), chain_id, tasks.scheduler.clone());
+        watcher::spawn_chain(state.clone(), chain_id, tasks.watcher.clone());
+        tasks
+    }
+
+    fn restart_watcher(&mut self, state: &AppState, chain_id: u64) {
+        self.watcher.cancel();
+        self.watcher = state.shutdown.child_token();
+        watcher::spawn_chain(state.clone(), chain_id, self.watcher.clone());
+    }
+
+    fn stop(&self) {
+        self.scheduler.cancel();
+        self.watcher.cancel();
+    }
+}
+
+/// Starts the per-chain scheduler and watcher tasks and reloads the config file on SIGHUP or
+/// when its modification time changes.
+pub fn start(state: AppState, path: PathBuf) {
+    let mut tasks: HashMap<u64, ChainTasks> = state
+        .rpcs
+        .chain_ids()
+        .into_iter()
+        .map(|chain_id| (chain_id, ChainTasks::spawn(&state, chain_id)))
+        .collect();
+
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => Some(hangup),
+            Err(err) => {
+                warn!(error = %err, "failed to install SIGHUP handler");
+                None
+            }
+        };
+        let mut modified = modified_at(&path);
+        let mut interval = tokio::time::interval(FILE_POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = state.shutdown.triggered() => return,
+                Some(_) = async { hangup.as_mut()?.recv().await }, if hangup.is_some() => {
+                    info!(path = %path.display(), "SIGHUP received, reloading config");
+                }
+                _ = interval.tick() => {
+                    let current = modified_at(&path);
+                    if current == modified {
+                        continue;
+                    }
+                    info!(path = %path.display(), "config file changed, reloading");
+                }
+            }
+            modified = modified_at(&path);
+
+            if let Err(err) = reload(&state, &path, &mut tasks).await {
+                error!(error = %err, "config reload failed, keeping previous config");
+            }
+        }
+    });
+}
+
+async fn reload(
+    state: &AppState,
+    path: &Path,
+    tasks: &mut HashMap<u64, ChainTasks>,
+) -> anyhow::Result<()> {
+    let mut next = Config::load_from_path(path)?;
+    let current = state.config.current();
+    keep_restart_only_sections(&current, &mut next);
+
+    let changes = state.rpcs.reload(&next).await?;
+    state.config.replace(next);
+
+    for chain_id in &changes.removed {
+        if let Some(chain_tasks) = tasks.remove(chain_id) {
+            chain_tasks.stop();
+        }
+    }
+    for chain_id in &changes.changed {
+        if let Some(chain_tasks) = tasks.get_mut(chain_id) {
+            chain_tasks.restart_watcher(state, *chain_id);
+        }
+    }
+    for chain_id in &changes.added {
+        tasks.insert(*chain_id, ChainTasks::spawn(state, *chain_id));
+    }
+
+    info!(
+        added = ?changes.added,
+        removed = ?changes.removed,
+        changed = ?changes.changed,
+        "config reloaded"
+    );
+    Ok(())
+}
+
+/// Sections bound at startup (listeners, pools, long-lived clients) are carried over from the
+/// running config; changing them requires a restart.
+fn keep_restart_only_sections(current: &Config, next: &mut Config) {
+    let mut ignored = Vec::new();
+    if next.server != current.server {
+        ignored.push("server");
+    }
+    if next.database != current.database {
+        ignored.push("database");
+    }
+    if next.redis != current.redis {
+        ignored.push("redis");
+    }
+    if next.api != current.api {
+        ignored.push("api");
+    }
+    // Webhook targets are checked against `allowed_hosts` on registration, delivery and every
+    // connect, all reading the live config, so the list takes effect without a restart.
+    let webhooks = WebhookConfig {
+        allowed_hosts: next.webhooks.allowed_hosts.clone(),
+        ..current.webhooks.clone()
+    };
+    if next.webhooks != webhooks {
+        ignored.push("webhooks");
+    }
+    if !ignored.is_empty() {
+        warn!(sections = ?ignored, "config sections changed but require a restart");
+    }
+
+    next.server = current.server.clone();
+    next.database = current.database.clone();
+    next.redis = current.redis.clone();
+    next.api = current.api.clone();
+    next.webhooks = webhooks;
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
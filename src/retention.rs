@@ -0,0 +1,210 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use tracing::{info, warn};
+
+use crate::config::{ArchiveTarget, RetentionConfig};
+use crate::db;
+use crate::state::AppState;
+
+static STATS: Mutex<RetentionStats> = Mutex::new(RetentionStats {
+    last_run_at: None,
+    stripped: 0,
+    archived: 0,
+    exported: 0,
+});
+
+/// Totals since this process started.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionStats {
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub stripped: u64,
+    pub archived: u64,
+    pub exported: u64,
+}
+
+pub fn stats() -> RetentionStats {
+    *STATS.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+pub fn start(state: AppState) {
+    tokio::spawn(async move {
+        run_retention_loop(state).await;
+    });
+}
+
+async fn run_retention_loop(state: AppState) {
+    loop {
+        let config = state.config.current().retention.clone();
+        if config.enabled
+            && let Err(err) = run_once(&state, &config).await
+        {
+            warn!(error = %err, "retention run failed");
+        }
+
+        tokio::select! {
+            _ = state.shutdown.triggered() => return,
+            _ = tokio::time::sleep(Duration::from_millis(config.interval_ms)) => {}
+        }
+    }
+}
+
+/// Applies every policy once. Rows are claimed with `SKIP LOCKED` and leased rows are left
+/// alone, so replicas can run this at the same time as each other and the scheduler.
+pub async fn run_once(
+    state: &AppState,
+    config: &RetentionConfig,
+) -> anyhow::Result<RetentionStats> {
+    let now = Utc::now();
+    let mut run = RetentionStats {
+        last_run_at: Some(now),
+        ..RetentionStats::default()
+    };
+    let mut oldest_archive_cutoff = None;
+
+    for (status, policy) in &config.policies {
+        if let Some(days) = policy.strip_raw_tx_after_days {
+            let before = cutoff(now, days);
+            run.stripped += in_batches(state, config.batch_size, || {
+                db::strip_raw_txs(&state.db, status, before, config.batch_size)
+            })
+            .await?;
+        }
+
+        if let Some(days) = policy.archive_after_days {
+            let before = cutoff(now, days);
+            oldest_archive_cutoff = Some(
+                oldest_archive_cutoff.map_or(before, |oldest: DateTime<Utc>| oldest.min(before)),
+            );
+            match config.archive {
+                ArchiveTarget::Table => {
+                    run.archived += in_batches(state, config.batch_size, || {
+                        db::archive_txs(&state.db, status, before, config.batch_size)
+                    })
+                    .await?;
+                }
+                ArchiveTarget::Jsonl => {
+                    tokio::fs::create_dir_all(&config.archive_dir).await?;
+                    run.exported += in_batches(state, config.batch_size, || {
+                        db::export_txs(&state.db, status, before, config.batch_size, |records| {
+                            let path = export_path(&config.archive_dir, status, Utc::now());
+                            async move {
+                                // Compressing and syncing the file would otherwise stall a
+                                // runtime worker.
+                                tokio::task::spawn_blocking(move || write_jsonl(&path, &records))
+                                    .await?
+                            }
+                        })
+                    })
+                    .await?;
+                }
+            }
+        }
+    }
+
+    if let Some(before) = oldest_archive_cutoff {
+        db::delete_orphan_fee_ladders(&state.db, before).await?;
+    }
+
+    if run.stripped + run.archived + run.exported > 0 {
+        info!(
+            stripped = run.stripped,
+            archived = run.archived,
+            exported = run.exported,
+            "retention run finished"
+        );
+    }
+    let mut stats = STATS.lock().unwrap_or_else(|err| err.into_inner());
+    stats.last_run_at = run.last_run_at;
+    stats.stripped += run.stripped;
+    stats.archived += run.archived;
+    stats.exported += run.exported;
+
+    Ok(run)
+}
+
+/// Repeats `step` until it handles less than a full batch or shutdown starts.
+async fn in_batches<F, Fut>(state: &AppState, batch_size: i64, mut step: F) -> anyhow::Result<u64>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<u64>>,
+{
+    let mut total = 0;
+    while !state.shutdown.is_triggered() {
+        let count = step().await?;
+        total += count;
+        if count < batch_size as u64 {
+            break;
+        }
+    }
+    Ok(total)
+}
+
+fn cutoff(now: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    now - chrono::Duration::days(i64::from(days))
+}
+
+fn export_path(dir: &Path, status: &str, now: DateTime<Utc>) -> PathBuf {
+    dir.join(format!(
+        "txs-{status}-{}.jsonl.gz",
+        now.format("%Y%m%dT%H%M%S%6fZ")
+    ))
+}
+
+/// Writes one record per line to a gzip file. The file only appears under its final name once
+/// it is complete and synced, so a failed run never leaves a partial export behind.
+fn write_jsonl(path: &Path, records: &[String]) -> anyhow::Result<()> {
+    let partial = path.with_extension("gz.partial");
+    let file = File::create(&partial)?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    for record in records {
+        encoder.write_all(record.as_bytes())?;
+        encoder.write_all(b"\n")?;
+    }
+    let file = encoder
+        .finish()?
+        .into_inner()
+        .map_err(|err| err.into_error())?;
+    file.sync_all()?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{export_path, write_jsonl};
+
+    #[test]
+    fn export_files_are_named_by_status_and_time() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 30, 5).unwrap();
+        let path = export_path("archive".as_ref(), "confirmed", now);
+        assert_eq!(
+            path.to_str(),
+            Some("archive/txs-confirmed-20250301T123005000000Z.jsonl.gz")
+        );
+    }
+
+    #[test]
+    fn write_jsonl_leaves_only_the_finished_file() {
+        let dir = std::env::temp_dir().join(format!("retention-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = export_path(&dir, "expired", Utc::now());
+
+        write_jsonl(&path, &[r#"{"id":1}"#.to_string()]).unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files, vec![path]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,64 +1,298 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
-use redis::AsyncCommands;
-use tokio::sync::Semaphore;
+use redis::{AsyncCommands, Script};
+use sqlx::postgres::PgListener;
+use tokio::sync::{Semaphore, broadcast};
+use tokio::time::Interval;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
-use uuid::Uuid;
 
 use crate::broadcaster::{self, BroadcastOutcome};
+use crate::config::SchedulerConfig;
 use crate::db;
+use crate::events;
+use crate::fee_ladder;
 use crate::models::{TxRecord, TxStatus};
-use crate::rpc::fetch_receipt;
+use crate::rpc::receipt_outcome;
 use crate::state::AppState;
+use crate::telemetry;
+
+// Checked while the reconciler is disabled, so a reload can turn it on.
+const RECONCILER_IDLE_MS: u64 = 5_000;
+// Upper bound on a Postgres-only scheduler's sleep, for work no notification announces such as
+// an expired lease.
+const LISTEN_MAX_WAIT: Duration = Duration::from_secs(30);
+const RELISTEN_DELAY: Duration = Duration::from_secs(1);
+const WAKEUP_BUFFER: usize = 1024;
+
+// Removes each member whose score still matches the snapshot; returns how many were removed.
+// A member rescheduled since the snapshot keeps its new score.
+static REMOVE_IF_UNCHANGED: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        -- remove_if_unchanged
+        local removed = 0
+        for i = 1, #ARGV, 2 do
+            local score = redis.call('ZSCORE', KEYS[1], ARGV[i])
+            if score and tonumber(score) == tonumber(ARGV[i + 1]) then
+                removed = removed + redis.call('ZREM', KEYS[1], ARGV[i])
+            end
+        end
+        return removed
+        "#,
+    )
+});
+
+/// Entries the reconciler added to or removed from one chain's Redis schedule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueDrift {
+    pub missing: usize,
+    pub orphaned: usize,
+}
+
+/// Wakes the per-chain schedulers when Postgres reports a newly schedulable row. Only used when
+/// running without Redis.
+#[derive(Clone)]
+pub struct Wakeups {
+    sender: broadcast::Sender<u64>,
+}
+
+impl Wakeups {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(WAKEUP_BUFFER);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<u64> {
+        self.sender.subscribe()
+    }
+
+    fn wake(&self, chain_id: u64) {
+        // No receivers just means no scheduler runs for that chain.
+        let _ = self.sender.send(chain_id);
+    }
+}
+
+impl Default for Wakeups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub fn start(state: AppState) {
-    for chain_id in state.rpcs.chain_ids() {
+    if state.redis.is_none() {
         let state = state.clone();
         tokio::spawn(async move {
-            run_chain_scheduler(state, chain_id).await;
+            run_listener(state).await;
         });
     }
+    for chain_id in state.rpcs.chain_ids() {
+        spawn_chain(state.clone(), chain_id, state.shutdown.child_token());
+    }
+}
+
+/// Runs the scheduler for one chain until `shutdown` is cancelled. Broadcasts already in flight
+/// keep their lease and finish on their own.
+pub fn spawn_chain(state: AppState, chain_id: u64, shutdown: CancellationToken) {
+    tokio::spawn(async move {
+        run_chain_scheduler(state, chain_id, shutdown).await;
+    });
 }
 
 pub async fn recover_after_restart(state: &AppState) -> anyhow::Result<()> {
     let recovered = db::recover_stuck_broadcasts(&state.db).await?;
-    if recovered.is_empty() {
-        return Ok(());
+    if !recovered.is_empty() {
+        schedule_records(state, &recovered).await?;
+        info!(
+            count = recovered.len(),
+            "recovered stuck broadcasts after restart"
+        );
     }
 
-    schedule_records(state, &recovered).await?;
-    info!(
-        count = recovered.len(),
-        "recovered stuck broadcasts after restart"
-    );
+    if state.redis.is_some() && state.config.current().scheduler.rebuild_redis_on_start {
+        for chain_id in state.rpcs.chain_ids() {
+            let scheduled = rebuild_queues(state, chain_id).await?;
+            info!(%chain_id, scheduled, "rebuilt redis schedule");
+        }
+    }
     Ok(())
 }
 
-async fn run_chain_scheduler(state: AppState, chain_id: u64) {
-    let config = state.config.clone();
-    let mut interval =
-        tokio::time::interval(Duration::from_millis(config.scheduler.poll_interval_ms));
-    let lease_owner = format!("scheduler:{}:{}", chain_id, Uuid::new_v4());
-    let semaphore = Arc::new(Semaphore::new(config.scheduler.max_concurrency));
+/// Periodically repairs the Redis schedule of every chain from Postgres.
+pub fn start_redis_reconciler(state: AppState) {
+    tokio::spawn(async move {
+        run_redis_reconciler(state).await;
+    });
+}
 
+async fn run_redis_reconciler(state: AppState) {
+    if state.redis.is_none() {
+        return;
+    }
     loop {
-        interval.tick().await;
+        let interval_ms = state.config.current().scheduler.redis_reconcile_interval_ms;
+        let delay_ms = if interval_ms == 0 {
+            RECONCILER_IDLE_MS
+        } else {
+            interval_ms
+        };
+        tokio::select! {
+            _ = state.shutdown.triggered() => return,
+            _ = tokio::time::sleep(Duration::from_millis(delay_ms)) => {}
+        }
+        if state.config.current().scheduler.redis_reconcile_interval_ms == 0 {
+            continue;
+        }
+
+        for chain_id in state.rpcs.chain_ids() {
+            if let Err(err) = reconcile_queues(&state, chain_id).await {
+                warn!(%chain_id, error = %err, "failed to reconcile redis schedule");
+            }
+        }
+    }
+}
+
+/// Brings the ready and retry ZSETs of `chain_id` in line with the schedulable rows in Postgres:
+/// entries for rows that are no longer queued, retrying or awaiting a rebroadcast are removed and
+/// rows waiting for a future slot are re-added.
+pub async fn reconcile_queues(state: &AppState, chain_id: u64) -> anyhow::Result<QueueDrift> {
+    let Some(mut redis) = state.redis.clone() else {
+        return Ok(QueueDrift::default());
+    };
+    let now = Utc::now().timestamp();
+    // Redis is read before Postgres so an entry added in between is never taken for an orphan.
+    let ready: Vec<(String, f64)> = redis.zrange_withscores(ready_key(chain_id), 0, -1).await?;
+    let retry: Vec<(String, f64)> = redis.zrange_withscores(retry_key(chain_id), 0, -1).await?;
+    let rows = db::list_scheduled_txs(&state.db, chain_id).await?;
+
+    let mut drift = QueueDrift::default();
+    for (key, statuses, actual) in [
+        (ready_key(chain_id), &[TxStatus::Queued][..], ready),
+        (
+            retry_key(chain_id),
+            &[TxStatus::RetryScheduled, TxStatus::Broadcasting][..],
+            retry,
+        ),
+    ] {
+        let expected: HashMap<String, i64> = rows
+            .iter()
+            .filter(|row| statuses.iter().any(|status| row.status == status.as_str()))
+            .map(|row| (bytes_to_hex(&row.tx_hash), row.next_action_at.timestamp()))
+            .collect();
+        let diff = diff_queue(&expected, &actual, now);
+
+        if !diff.missing.is_empty() {
+            let mut pipe = redis::pipe();
+            for (tx_hash, score) in &diff.missing {
+                pipe.zadd(&key, tx_hash, *score).ignore();
+            }
+            pipe.query_async::<()>(&mut redis).await?;
+            drift.missing += diff.missing.len();
+        }
+        if !diff.orphaned.is_empty() {
+            let mut invocation = REMOVE_IF_UNCHANGED.key(&key);
+            for (tx_hash, score) in &diff.orphaned {
+                invocation.arg(tx_hash).arg(*score);
+            }
+            let removed: usize = invocation.invoke_async(&mut redis).await?;
+            drift.orphaned += removed;
+        }
+    }
+
+    if drift != QueueDrift::default() {
+        telemetry::record_redis_drift(chain_id, "missing", drift.missing);
+        telemetry::record_redis_drift(chain_id, "orphaned", drift.orphaned);
+        warn!(
+            %chain_id,
+            missing = drift.missing,
+            orphaned = drift.orphaned,
+            "repaired redis schedule drift"
+        );
+    }
+    Ok(drift)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct QueueDiff {
+    missing: Vec<(String, i64)>,
+    orphaned: Vec<(String, i64)>,
+}
+
+/// Compares one ZSET with the rows that belong in it (`expected`, hash to score). Rows that are
+/// already due may be missing on purpose: the scheduler drops entries it could not lease, for
+/// example behind a pending lower nonce, and the database poll picks those up.
+fn diff_queue(expected: &HashMap<String, i64>, actual: &[(String, f64)], now: i64) -> QueueDiff {
+    let actual: HashMap<&str, i64> = actual
+        .iter()
+        .map(|(tx_hash, score)| (tx_hash.as_str(), *score as i64))
+        .collect();
+
+    let mut diff = QueueDiff::default();
+    for (tx_hash, score) in expected {
+        if *score > now && actual.get(tx_hash.as_str()) != Some(score) {
+            diff.missing.push((tx_hash.clone(), *score));
+        }
+    }
+    for (tx_hash, score) in actual {
+        if !expected.contains_key(tx_hash) {
+            diff.orphaned.push((tx_hash.to_string(), score));
+        }
+    }
+    diff.missing.sort();
+    diff.orphaned.sort();
+    diff
+}
+
+async fn run_chain_scheduler(state: AppState, chain_id: u64, shutdown: CancellationToken) {
+    let config = state.config.current();
+    let mut poll_interval_ms = config.scheduler.poll_interval_ms;
+    let mut interval = tokio::time::interval(Duration::from_millis(poll_interval_ms));
+    let lease_owner = state.shutdown.scheduler_lease_owner(chain_id);
+    let mut capacity = config.scheduler.max_concurrency;
+    let semaphore = Arc::new(Semaphore::new(capacity));
+    let mut wakeups = state.redis.is_none().then(|| state.wakeups.subscribe());
+    let mut backlog = true;
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                info!(%chain_id, "scheduler stopped");
+                return;
+            }
+            _ = next_tick(&state, chain_id, &mut interval, wakeups.as_mut(), backlog) => {}
+        }
+
+        let config = state.config.current();
+        if config.scheduler.poll_interval_ms != poll_interval_ms {
+            poll_interval_ms = config.scheduler.poll_interval_ms;
+            interval = tokio::time::interval(Duration::from_millis(poll_interval_ms));
+        }
+        resize_semaphore(&semaphore, &mut capacity, config.scheduler.max_concurrency);
 
         let available = semaphore.available_permits();
         if available == 0 {
+            backlog = true;
             continue;
         }
 
         let now = Utc::now();
         let lease_until = now + chrono::Duration::seconds(config.scheduler.lease_ttl_seconds);
 
-        let mut redis = state.redis.clone();
         let mut leased = Vec::new();
 
-        match fetch_due_from_redis(&mut redis, chain_id, now, available).await {
-            Ok(due) => {
+        let redis_due = match state.redis.clone() {
+            Some(mut redis) => Some((
+                fetch_due_from_redis(&mut redis, chain_id, now, available).await,
+                redis,
+            )),
+            None => None,
+        };
+        match redis_due {
+            None => {}
+            Some((Ok(due), mut redis)) => {
                 for hash in due {
                     if let Ok(tx_hash) = parse_hex_hash(&hash) {
                         match db::lease_tx_by_hash(
@@ -68,6 +302,7 @@ async fn run_chain_scheduler(state: AppState, chain_id: u64) {
                             now,
                             &lease_owner,
                             lease_until,
+                            config.scheduler.nonce_lookahead,
                         )
                         .await
                         {
@@ -85,7 +320,7 @@ async fn run_chain_scheduler(state: AppState, chain_id: u64) {
                     let _: () = redis.zrem(retry_key, &hash).await.unwrap_or(());
                 }
             }
-            Err(err) => {
+            Some((Err(err), _)) => {
                 warn!(error = %err, "failed to fetch due txs from redis");
             }
         }
@@ -99,6 +334,7 @@ async fn run_chain_scheduler(state: AppState, chain_id: u64) {
                 &lease_owner,
                 lease_until,
                 remaining as i64,
+                config.scheduler.nonce_lookahead,
             )
             .await
             {
@@ -106,6 +342,7 @@ async fn run_chain_scheduler(state: AppState, chain_id: u64) {
                 Err(err) => warn!(error = %err, "failed to lease due txs from db"),
             }
         }
+        backlog = leased.len() >= available;
 
         for record in leased {
             let state = state.clone();
@@ -119,7 +356,7 @@ async fn run_chain_scheduler(state: AppState, chain_id: u64) {
                 }
             };
 
-            tokio::spawn(async move {
+            state.shutdown.clone().spawn(async move {
                 let _permit = permit;
                 if let Err(err) = handle_broadcast(state, chain_id, record, lease_owner).await {
                     error!(error = %err, "broadcast attempt failed");
@@ -129,38 +366,148 @@ async fn run_chain_scheduler(state: AppState, chain_id: u64) {
     }
 }
 
+/// Waits for the next scheduling pass. With Redis, and while there is more due work than
+/// permits, that is the poll interval. Otherwise the scheduler sleeps until its next row falls
+/// due or Postgres reports a newly schedulable row on this chain.
+async fn next_tick(
+    state: &AppState,
+    chain_id: u64,
+    interval: &mut Interval,
+    wakeups: Option<&mut broadcast::Receiver<u64>>,
+    backlog: bool,
+) {
+    let Some(wakeups) = wakeups.filter(|_| !backlog) else {
+        interval.tick().await;
+        return;
+    };
+
+    let now = Utc::now();
+    let wait = match db::next_action_after(&state.db, chain_id, now).await {
+        Ok(Some(next_action_at)) => (next_action_at - now)
+            .to_std()
+            .unwrap_or_default()
+            .min(LISTEN_MAX_WAIT),
+        Ok(None) => LISTEN_MAX_WAIT,
+        Err(err) => {
+            warn!(%chain_id, error = %err, "failed to load next due tx");
+            interval.tick().await;
+            return;
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::time::sleep(wait) => {}
+        _ = wakeup_for(wakeups, chain_id) => {}
+    }
+}
+
+async fn wakeup_for(wakeups: &mut broadcast::Receiver<u64>, chain_id: u64) {
+    loop {
+        match wakeups.recv().await {
+            Ok(woken) if woken != chain_id => continue,
+            Err(broadcast::error::RecvError::Closed) => std::future::pending().await,
+            // A lagged receiver may have missed this chain's wake-up.
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => return,
+        }
+    }
+}
+
+async fn run_listener(state: AppState) {
+    loop {
+        if let Err(err) = listen(&state).await {
+            warn!(error = %err, "tx notification listener failed");
+        }
+        tokio::select! {
+            _ = state.shutdown.triggered() => return,
+            _ = tokio::time::sleep(RELISTEN_DELAY) => {}
+        }
+    }
+}
+
+async fn listen(state: &AppState) -> anyhow::Result<()> {
+    let mut listener = PgListener::connect_with(&state.db).await?;
+    listener.listen(db::TX_NOTIFY_CHANNEL).await?;
+    info!(
+        channel = db::TX_NOTIFY_CHANNEL,
+        "listening for schedulable txs"
+    );
+    // Anything that changed before the listener was up has no notification.
+    wake_all(state);
+
+    loop {
+        let notification = tokio::select! {
+            _ = state.shutdown.triggered() => return Ok(()),
+            notification = listener.try_recv() => notification?,
+        };
+        let Some(notification) = notification else {
+            // The connection dropped and is re-established on the next call; notifications sent
+            // in between are lost.
+            wake_all(state);
+            continue;
+        };
+        match notification.payload().parse::<u64>() {
+            Ok(chain_id) => state.wakeups.wake(chain_id),
+            Err(_) => warn!(payload = notification.payload(), "invalid tx notification"),
+        }
+    }
+}
+
+fn wake_all(state: &AppState) {
+    for chain_id in state.rpcs.chain_ids() {
+        state.wakeups.wake(chain_id);
+    }
+}
+
+/// Grows or shrinks the scheduler's permit pool towards `target`. Permits held by in-flight
+/// broadcasts can't be reclaimed, so shrinking may take several ticks.
+fn resize_semaphore(semaphore: &Semaphore, capacity: &mut usize, target: usize) {
+    if target > *capacity {
+        semaphore.add_permits(target - *capacity);
+        *capacity = target;
+    } else if target < *capacity {
+        *capacity -= semaphore.forget_permits(*capacity - target);
+    }
+}
+
 async fn handle_broadcast(
     state: AppState,
     chain_id: u64,
     record: TxRecord,
     lease_owner: String,
 ) -> anyhow::Result<()> {
+    let config = state.config.current();
     let now = Utc::now();
     if let Some(expires_at) = record.expires_at
         && expires_at <= now
     {
-        let _ = db::mark_terminal_if_leased(
+        if let Some(updated) = db::mark_terminal_if_leased(
             &state.db,
             record.id,
             lease_owner.as_str(),
             TxStatus::Expired.as_str(),
             None,
         )
-        .await?;
+        .await?
+        {
+            events::publish(&state, &updated).await;
+        }
         return Ok(());
     }
 
     let raw_tx = match record.raw_tx.as_ref() {
         Some(raw) => raw,
         None => {
-            let _ = db::mark_terminal_if_leased(
+            if let Some(updated) = db::mark_terminal_if_leased(
                 &state.db,
                 record.id,
                 lease_owner.as_str(),
                 TxStatus::Invalid.as_str(),
                 Some("missing raw_tx"),
             )
-            .await?;
+            .await?
+            {
+                events::publish(&state, &updated).await;
+            }
             return Ok(());
         }
     };
@@ -170,23 +517,42 @@ async fn handle_broadcast(
         .chain(chain_id)
         .ok_or_else(|| anyhow::anyhow!("missing rpc chain"))?;
 
-    if let Some(receipt) = fetch_receipt(chain, &record).await? {
+    if let Some((mined, receipt)) = fee_ladder::find_receipt(&state, &chain, &record).await? {
         info!(
             %chain_id,
-            tx_hash = %bytes_to_hex(&record.tx_hash),
+            tx_hash = %bytes_to_hex(&mined.tx_hash),
             "transaction already executed"
         );
-        let receipt_json = serde_json::to_value(receipt)?;
-        db::mark_executed(&state.db, record.id, receipt_json).await?;
+        let outcome = receipt_outcome(&receipt)?;
+        if let Some(updated) = db::mark_executed(&state.db, mined.id, &outcome).await? {
+            telemetry::record_executed(&updated);
+            events::publish(&state, &updated).await;
+            let was_replaced = mined.status == TxStatus::Replaced.as_str();
+            fee_ladder::retire_siblings(&state, &updated, was_replaced).await?;
+            wake_nonce_successors(
+                &state,
+                chain_id,
+                &updated.sender,
+                &updated.nonce_key,
+                updated.nonce.to_uint().saturating_add(1),
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
+    if fee_ladder::escalate_if_due(&state, &record, &lease_owner, now).await? {
         return Ok(());
     }
 
     let tx_hash = bytes_to_hex(&record.tx_hash);
     let outcome = broadcaster::broadcast_raw_tx(
-        chain,
+        &state.db,
+        record.id,
+        &chain,
         raw_tx,
-        state.config.broadcaster.fanout,
-        Duration::from_millis(state.config.broadcaster.timeout_ms),
+        config.broadcaster.fanout,
+        Duration::from_millis(config.broadcaster.timeout_ms),
         record.attempts,
     )
     .await;
@@ -195,6 +561,7 @@ async fn handle_broadcast(
 
     match outcome {
         BroadcastOutcome::Accepted { error } => {
+            telemetry::record_broadcast(chain_id, "accepted");
             info!(
                 %chain_id,
                 tx_hash = %tx_hash,
@@ -204,7 +571,7 @@ async fn handle_broadcast(
                 "transaction broadcasted",
             );
             let next_action_at =
-                schedule_next_attempt(now, record.expires_at, attempts as u64, &state);
+                schedule_next_attempt(now, record.expires_at, attempts as u64, &config.scheduler);
             let updated = db::reschedule_tx_if_leased(
                 &state.db,
                 record.id,
@@ -215,11 +582,13 @@ async fn handle_broadcast(
                 error.as_deref(),
             )
             .await?;
-            if updated {
+            if let Some(updated) = updated {
                 update_retry_schedule(&state, chain_id, &record.tx_hash, next_action_at).await?;
+                events::publish(&state, &updated).await;
             }
         }
         BroadcastOutcome::Retry { error } => {
+            telemetry::record_broadcast(chain_id, "retry");
             warn!(
                 %chain_id,
                 tx_hash = %tx_hash,
@@ -229,7 +598,7 @@ async fn handle_broadcast(
                 "transaction broadcasted",
             );
             let next_action_at =
-                schedule_next_attempt(now, record.expires_at, attempts as u64, &state);
+                schedule_next_attempt(now, record.expires_at, attempts as u64, &config.scheduler);
             let updated = db::reschedule_tx_if_leased(
                 &state.db,
                 record.id,
@@ -240,11 +609,13 @@ async fn handle_broadcast(
                 Some(&error),
             )
             .await?;
-            if updated {
+            if let Some(updated) = updated {
                 update_retry_schedule(&state, chain_id, &record.tx_hash, next_action_at).await?;
+                events::publish(&state, &updated).await;
             }
         }
         BroadcastOutcome::Invalid { error } => {
+            telemetry::record_broadcast(chain_id, "invalid");
             warn!(
                 %chain_id,
                 tx_hash = %tx_hash,
@@ -253,21 +624,62 @@ async fn handle_broadcast(
                 error = %error,
                 "transaction broadcasted",
             );
-            let _ = db::mark_terminal_if_leased(
+            if let Some(updated) = db::mark_terminal_if_leased(
                 &state.db,
                 record.id,
                 lease_owner.as_str(),
                 TxStatus::Invalid.as_str(),
                 Some(&error),
             )
-            .await?;
+            .await?
+            {
+                events::publish(&state, &updated).await;
+            }
         }
     }
 
     Ok(())
 }
 
+/// Wakes the transactions waiting behind `current_nonce` for `(sender, nonce_key)`. Called once
+/// the chain has moved past a pending nonce.
+pub async fn wake_nonce_successors(
+    state: &AppState,
+    chain_id: u64,
+    sender: &[u8],
+    nonce_key: &[u8],
+    current_nonce: u64,
+) -> anyhow::Result<()> {
+    let woken = db::wake_nonce_successors(
+        &state.db,
+        chain_id,
+        sender,
+        nonce_key,
+        current_nonce,
+        state.config.current().scheduler.nonce_lookahead,
+    )
+    .await?;
+    if woken.is_empty() {
+        return Ok(());
+    }
+
+    schedule_records(state, &woken).await?;
+    info!(
+        %chain_id,
+        sender = %bytes_to_hex(sender),
+        current_nonce,
+        count = woken.len(),
+        "woke nonce successors"
+    );
+    Ok(())
+}
+
+/// Adds `records` to the Redis schedule. A no-op without Redis, where the scheduler finds due
+/// rows in Postgres.
 pub async fn schedule_records(state: &AppState, records: &[TxRecord]) -> anyhow::Result<()> {
+    let Some(mut redis) = state.redis.clone() else {
+        return Ok(());
+    };
     let mut pipe = redis::pipe();
     pipe.atomic();
     let mut has_ops = false;
@@ -279,7 +691,9 @@ pub async fn schedule_records(state: &AppState, records: &[TxRecord]) -> anyhow:
         };
         let (key, score) = match record.status.as_str() {
             "queued" => (ready_key(record.chain_id.to_uint()), next_action_at),
-            "retry_scheduled" => (retry_key(record.chain_id.to_uint()), next_action_at),
+            "retry_scheduled" | "broadcasting" => {
+                (retry_key(record.chain_id.to_uint()), next_action_at)
+            }
             _ => continue,
         };
         let tx_hash = bytes_to_hex(&record.tx_hash);
@@ -292,19 +706,68 @@ pub async fn schedule_records(state: &AppState, records: &[TxRecord]) -> anyhow:
         return Ok(());
     }
 
-    let mut redis = state.redis.clone();
     pipe.query_async::<()>(&mut redis).await?;
     Ok(())
 }
 
+/// Removes `records` from the Redis schedule, e.g. after they were canceled.
+pub async fn unschedule_records(state: &AppState, records: &[TxRecord]) -> anyhow::Result<()> {
+    let Some(mut redis) = state.redis.clone() else {
+        return Ok(());
+    };
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for record in records {
+        let chain_id = record.chain_id.to_uint();
+        let tx_hash = bytes_to_hex(&record.tx_hash);
+        pipe.zrem(ready_key(chain_id), &tx_hash).ignore();
+        pipe.zrem(retry_key(chain_id), tx_hash).ignore();
+    }
+
+    pipe.query_async::<()>(&mut redis).await?;
+    Ok(())
+}
+
+/// Replaces the Redis schedule for `chain_id` with what the database says is due. Returns the
+/// number of transactions scheduled.
+pub async fn rebuild_queues(state: &AppState, chain_id: u64) -> anyhow::Result<usize> {
+    let Some(mut redis) = state.redis.clone() else {
+        anyhow::bail!("redis is not configured");
+    };
+    let records = db::list_active_txs(&state.db, chain_id).await?;
+
+    redis::pipe()
+        .del(ready_key(chain_id))
+        .ignore()
+        .del(retry_key(chain_id))
+        .ignore()
+        .query_async::<()>(&mut redis)
+        .await?;
+    schedule_records(state, &records).await?;
+
+    Ok(records
+        .iter()
+        .filter(|record| {
+            record.next_action_at.is_some()
+                && (record.status == TxStatus::Queued.as_str()
+                    || record.status == TxStatus::RetryScheduled.as_str()
+                    || record.status == TxStatus::Broadcasting.as_str())
+        })
+        .count())
+}
+
 fn schedule_next_attempt(
     now: DateTime<Utc>,
     expires_at: Option<DateTime<Utc>>,
     attempts: u64,
-    state: &AppState,
+    config: &SchedulerConfig,
 ) -> DateTime<Utc> {
-    let max_retry_ms = max_retry_ms_for(now, expires_at, &state.config.scheduler);
-    let delay_ms = retry_backoff_ms(attempts, state.config.scheduler.retry_min_ms, max_retry_ms);
+    let max_retry_ms = max_retry_ms_for(now, expires_at, config);
+    let delay_ms = retry_backoff_ms(attempts, config.retry_min_ms, max_retry_ms);
     let mut next_action_at = now + chrono::Duration::milliseconds(delay_ms as i64);
     if let Some(expires_at) = expires_at
         && next_action_at > expires_at
@@ -317,7 +780,7 @@ fn schedule_next_attempt(
 fn max_retry_ms_for(
     now: DateTime<Utc>,
     expires_at: Option<DateTime<Utc>>,
-    config: &crate::config::SchedulerConfig,
+    config: &SchedulerConfig,
 ) -> u64 {
     let mut max_retry_ms = config.retry_max_ms;
     if let Some(expires_at) = expires_at
@@ -328,7 +791,7 @@ fn max_retry_ms_for(
     max_retry_ms
 }
 
-fn retry_backoff_ms(attempts: u64, min_ms: u64, max_ms: u64) -> u64 {
+pub(crate) fn retry_backoff_ms(attempts: u64, min_ms: u64, max_ms: u64) -> u64 {
     let shift = attempts.saturating_sub(1).min(10);
     let delay = min_ms.saturating_mul(1u64 << shift);
     delay.clamp(min_ms, max_ms)
@@ -340,7 +803,9 @@ async fn update_retry_schedule(
     tx_hash: &[u8],
     next_action_at: DateTime<Utc>,
 ) -> anyhow::Result<()> {
-    let mut redis = state.redis.clone();
+    let Some(mut redis) = state.redis.clone() else {
+        return Ok(());
+    };
     let tx_hash = bytes_to_hex(tx_hash);
     let ready_key = ready_key(chain_id);
     let retry_key = retry_key(chain_id);
@@ -404,7 +869,11 @@ fn retry_key(chain_id: u64) -> String {
 mod tests {
     use chrono::{Duration, Utc};
 
-    use super::{max_retry_ms_for, retry_backoff_ms};
+    use tokio::sync::Semaphore;
+
+    use std::collections::HashMap;
+
+    use super::{QueueDiff, diff_queue, max_retry_ms_for, resize_semaphore, retry_backoff_ms};
     use crate::config::SchedulerConfig;
 
     #[test]
@@ -427,6 +896,11 @@ mod tests {
             retry_max_ms: 60_000,
             expiry_soon_window_seconds: 3600,
             expiry_soon_retry_max_ms: 5_000,
+            escalate_after_attempts: 3,
+            escalate_after_seconds: 60,
+            nonce_lookahead: 0,
+            redis_reconcile_interval_ms: 60_000,
+            rebuild_redis_on_start: false,
         };
 
         let now = Utc::now();
@@ -437,4 +911,51 @@ mod tests {
         assert_eq!(max_retry_ms_for(now, Some(soon_expiry), &config), 5_000);
         assert_eq!(max_retry_ms_for(now, None, &config), 60_000);
     }
+
+    #[test]
+    fn resize_semaphore_waits_for_held_permits() {
+        let semaphore = Semaphore::new(4);
+        let mut capacity = 4;
+
+        resize_semaphore(&semaphore, &mut capacity, 6);
+        assert_eq!(capacity, 6);
+        assert_eq!(semaphore.available_permits(), 6);
+
+        let held = semaphore.try_acquire_many(5).expect("permits");
+        resize_semaphore(&semaphore, &mut capacity, 2);
+        assert_eq!(capacity, 5);
+        assert_eq!(semaphore.available_permits(), 0);
+
+        drop(held);
+        resize_semaphore(&semaphore, &mut capacity, 2);
+        assert_eq!(capacity, 2);
+        assert_eq!(semaphore.available_permits(), 2);
+    }
+
+    #[test]
+    fn diff_queue_readds_future_entries_and_drops_orphans() {
+        let now = 1_000;
+        let expected = HashMap::from([
+            ("0xfuture".to_string(), 1_500),
+            ("0xrescheduled".to_string(), 2_000),
+            ("0xdue".to_string(), 900),
+            ("0xpresent".to_string(), 1_200),
+        ]);
+        let actual = vec![
+            ("0xrescheduled".to_string(), 1_100.0),
+            ("0xpresent".to_string(), 1_200.0),
+            ("0xgone".to_string(), 800.0),
+        ];
+
+        assert_eq!(
+            diff_queue(&expected, &actual, now),
+            QueueDiff {
+                missing: vec![
+                    ("0xfuture".to_string(), 1_500),
+                    ("0xrescheduled".to_string(), 2_000),
+                ],
+                orphaned: vec![("0xgone".to_string(), 800)],
+            }
+        );
+    }
 }
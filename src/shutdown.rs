@@ -0,0 +1,122 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::signal::unix::{SignalKind, signal};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db;
+use crate::scheduler;
+use crate::state::AppState;
+
+/// Coordinates process shutdown: background loops watch the token, in-flight broadcasts are
+/// tracked so they can be awaited, and scheduler leases carry this process's id so whatever is
+/// still held at exit can be released.
+#[derive(Clone)]
+pub struct Shutdown {
+    token: CancellationToken,
+    tracker: TaskTracker,
+    instance_id: Uuid,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            tracker: TaskTracker::new(),
+            instance_id: Uuid::new_v4(),
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    pub async fn triggered(&self) {
+        self.token.cancelled().await;
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Token for a task that must stop on shutdown but can also be stopped on its own.
+    pub fn child_token(&self) -> CancellationToken {
+        self.token.child_token()
+    }
+
+    /// Spawns work that shutdown waits for before releasing leases.
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.tracker.spawn(task);
+    }
+
+    pub fn scheduler_lease_owner(&self, chain_id: u64) -> String {
+        format!("{}{chain_id}", self.scheduler_lease_prefix())
+    }
+
+    fn scheduler_lease_prefix(&self) -> String {
+        format!("scheduler:{}:", self.instance_id)
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolves on SIGTERM or Ctrl-C.
+pub async fn signal_received() {
+    let terminate = async {
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(err) => {
+                warn!(error = %err, "failed to install SIGTERM handler");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
+/// Waits up to `server.shutdown_timeout_ms` for in-flight broadcasts, then hands every lease this
+/// process still holds back to the queue so other replicas can pick the rows up immediately.
+pub async fn drain(state: &AppState) {
+    let shutdown = &state.shutdown;
+    shutdown.trigger();
+    shutdown.tracker.close();
+
+    let deadline = Duration::from_millis(state.config.current().server.shutdown_timeout_ms);
+    if tokio::time::timeout(deadline, shutdown.tracker.wait())
+        .await
+        .is_err()
+    {
+        warn!(
+            in_flight = shutdown.tracker.len(),
+            "shutdown deadline reached with broadcasts in flight"
+        );
+    }
+
+    let released = match db::release_leases(&state.db, &shutdown.scheduler_lease_prefix()).await {
+        Ok(released) => released,
+        Err(err) => {
+            warn!(error = %err, "failed to release leases");
+            return;
+        }
+    };
+    if let Err(err) = scheduler::schedule_records(state, &released).await {
+        warn!(error = %err, "failed to requeue released txs");
+    }
+    info!(count = released.len(), "released leases");
+}
@@ -4,13 +4,18 @@ use chrono::{DateTime, Utc};
 use redis::aio::ConnectionManager;
 use sqlx::PgPool;
 
-use crate::{config::Config, rpc::RpcManager};
+use crate::{
+    config::ConfigHandle, events::EventBus, rpc::RpcManager, scheduler::Wakeups, shutdown::Shutdown,
+};
 
 #[derive(Clone)]
 pub struct AppState {
-    pub config: Arc<Config>,
+    pub config: ConfigHandle,
     pub db: PgPool,
-    pub redis: ConnectionManager,
+    pub redis: Option<ConnectionManager>,
     pub rpcs: Arc<RpcManager>,
+    pub events: EventBus,
+    pub wakeups: Wakeups,
+    pub shutdown: Shutdown,
     pub started_at: DateTime<Utc>,
 }
@@ -0,0 +1,169 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
+use tracing::warn;
+
+use crate::db;
+use crate::models::{TxRecord, TxStatus};
+use crate::state::AppState;
+
+const TXS_INGESTED: &str = "watchtower_txs_ingested_total";
+const BROADCASTS: &str = "watchtower_broadcasts_total";
+const WATCHER_TRANSITIONS: &str = "watchtower_watcher_transitions_total";
+const QUEUE_DEPTH: &str = "watchtower_queue_depth";
+const TXS_BY_STATUS: &str = "watchtower_txs";
+const TIME_TO_EXECUTED: &str = "watchtower_time_to_executed_seconds";
+const RPC_REQUEST_DURATION: &str = "watchtower_rpc_request_duration_seconds";
+const RPC_ERRORS: &str = "watchtower_rpc_errors_total";
+const REDIS_DRIFT: &str = "watchtower_redis_drift_total";
+
+const TIME_TO_EXECUTED_BUCKETS: &[f64] = &[
+    1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 900.0, 1800.0, 3600.0, 21600.0, 86400.0,
+];
+const RPC_LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const STATUSES: [TxStatus; 12] = [
+    TxStatus::Queued,
+    TxStatus::Broadcasting,
+    TxStatus::RetryScheduled,
+    TxStatus::Executed,
+    TxStatus::Confirmed,
+    TxStatus::Expired,
+    TxStatus::Invalid,
+    TxStatus::StaleByNonce,
+    TxStatus::CanceledLocally,
+    TxStatus::Replaced,
+    TxStatus::Standby,
+    TxStatus::Paused,
+];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the global Prometheus recorder. Safe to call more than once.
+pub fn init() -> &'static PrometheusHandle {
+    HANDLE.get_or_init(|| {
+        PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                Matcher::Full(TIME_TO_EXECUTED.to_string()),
+                TIME_TO_EXECUTED_BUCKETS,
+            )
+            .and_then(|builder| {
+                builder.set_buckets_for_metric(
+                    Matcher::Full(RPC_REQUEST_DURATION.to_string()),
+                    RPC_LATENCY_BUCKETS,
+                )
+            })
+            .expect("metric buckets are non-empty")
+            .install_recorder()
+            .expect("prometheus recorder installed once")
+    })
+}
+
+/// Refreshes the scrape-time gauges and renders every series in Prometheus text format.
+pub async fn render(state: &AppState) -> String {
+    let handle = init();
+    if let Err(err) = refresh_gauges(state).await {
+        warn!(error = %err, "failed to refresh metric gauges");
+    }
+    handle.run_upkeep();
+    handle.render()
+}
+
+pub fn record_ingested(chain_id: u64) {
+    counter!(TXS_INGESTED, "chain_id" => chain_id.to_string()).increment(1);
+}
+
+pub fn record_broadcast(chain_id: u64, outcome: &'static str) {
+    counter!(BROADCASTS, "chain_id" => chain_id.to_string(), "outcome" => outcome).increment(1);
+}
+
+pub fn record_watcher_transition(chain_id: u64, status: TxStatus) {
+    counter!(
+        WATCHER_TRANSITIONS,
+        "chain_id" => chain_id.to_string(),
+        "status" => status.as_str()
+    )
+    .increment(1);
+}
+
+/// Records how long `record` took from ingestion until it was marked executed.
+pub fn record_executed(record: &TxRecord) {
+    let elapsed = record.updated_at.signed_duration_since(record.created_at);
+    let seconds = elapsed.num_milliseconds().max(0) as f64 / 1000.0;
+    histogram!(TIME_TO_EXECUTED, "chain_id" => record.chain_id.to_uint().to_string())
+        .record(seconds);
+}
+
+/// `endpoint` must already be redacted, see [`crate::rpc::redact_url`].
+pub fn record_rpc_request(chain_id: u64, endpoint: &str, latency: Duration, failed: bool) {
+    let labels = [
+        ("chain_id", chain_id.to_string()),
+        ("endpoint", endpoint.to_string()),
+    ];
+    histogram!(RPC_REQUEST_DURATION, &labels).record(latency.as_secs_f64());
+    if failed {
+        counter!(RPC_ERRORS, &labels).increment(1);
+    }
+}
+
+/// Counts Redis schedule entries the reconciler had to add (`missing`) or remove (`orphaned`).
+pub fn record_redis_drift(chain_id: u64, kind: &'static str, count: usize) {
+    counter!(REDIS_DRIFT, "chain_id" => chain_id.to_string(), "kind" => kind)
+        .increment(count as u64);
+}
+
+async fn refresh_gauges(state: &AppState) -> anyhow::Result<()> {
+    let chain_ids = state.rpcs.chain_ids();
+    // Without Redis there are no queues; `watchtower_txs` still covers the backlog.
+    if let Some(mut redis) = state.redis.clone() {
+        refresh_queue_depth(&mut redis, &chain_ids).await?;
+    }
+
+    let counts = db::count_txs_by_status(&state.db).await?;
+    for chain_id in chain_ids {
+        for status in STATUSES {
+            let count = counts
+                .iter()
+                .find(|row| row.chain_id.to_uint() == chain_id && row.status == status.as_str())
+                .map(|row| row.count)
+                .unwrap_or(0);
+            gauge!(
+                TXS_BY_STATUS,
+                "chain_id" => chain_id.to_string(),
+                "status" => status.as_str()
+            )
+            .set(count as f64);
+        }
+    }
+
+    Ok(())
+}
+
+async fn refresh_queue_depth(
+    redis: &mut ConnectionManager,
+    chain_ids: &[u64],
+) -> anyhow::Result<()> {
+    for chain_id in chain_ids {
+        let ready: u64 = redis.zcard(ready_key(*chain_id)).await?;
+        let retry: u64 = redis.zcard(retry_key(*chain_id)).await?;
+        gauge!(QUEUE_DEPTH, "chain_id" => chain_id.to_string(), "queue" => "ready")
+            .set(ready as f64);
+        gauge!(QUEUE_DEPTH, "chain_id" => chain_id.to_string(), "queue" => "retry")
+            .set(retry as f64);
+    }
+    Ok(())
+}
+
+fn ready_key(chain_id: u64) -> String {
+    format!("watchtower:ready:{chain_id}")
+}
+
+fn retry_key(chain_id: u64) -> String {
+    format!("watchtower:retry:{chain_id}")
+}
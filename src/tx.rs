@@ -27,6 +27,22 @@ pub struct ParsedTx {
     pub calls: Option<Vec<Call>>,
 }
 
+impl ParsedTx {
+    /// Every address the transaction calls: `to`, plus each Tempo `calls[].to`.
+    pub fn call_targets(&self) -> Vec<Address> {
+        let mut targets: Vec<Address> = self
+            .calls
+            .iter()
+            .flatten()
+            .filter_map(|call| call.to.to().copied())
+            .chain(self.to)
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+        targets
+    }
+}
+
 pub fn parse_raw_tx(raw_hex: &str) -> Result<ParsedTx> {
     let raw_hex = raw_hex.strip_prefix("0x").unwrap_or(raw_hex);
     let raw_tx = hex::decode(raw_hex).context("decode raw tx hex")?;
@@ -1,61 +1,86 @@
-use std::collections::BTreeMap;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
 
-use alloy::network::TransactionBuilder;
+use alloy::consensus::BlockHeader;
+use alloy::network::ReceiptResponse;
+use alloy::primitives::B256;
 use alloy::providers::Provider;
 use alloy_rpc_types_eth::BlockId;
 use chrono::Utc;
+use tempo_alloy::rpc::TempoTransactionReceipt;
 use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
+use uuid::Uuid;
 
 use crate::db;
-use crate::models::TxRecord;
-use crate::rpc::{ChainRpc, fetch_receipt};
+use crate::events;
+use crate::fee_ladder;
+use crate::models::{TxRecord, TxStatus};
+use crate::rpc::{ChainRpc, fetch_current_nonce, fetch_receipt, receipt_outcome};
+use crate::scheduler;
 use crate::state::AppState;
+use crate::telemetry;
+
+/// Cap on blocks read in one tick when the watcher falls behind.
+const MAX_BLOCKS_PER_TICK: u64 = 64;
 
 pub fn start(state: AppState) {
     for chain_id in state.rpcs.chain_ids() {
-        let state = state.clone();
-        tokio::spawn(async move {
-            run_chain_watcher(state, chain_id).await;
-        });
+        spawn_chain(state.clone(), chain_id, state.shutdown.child_token());
     }
 }
 
-async fn run_chain_watcher(state: AppState, chain_id: u64) {
+/// Runs the watcher for one chain until `shutdown` is cancelled.
+pub fn spawn_chain(state: AppState, chain_id: u64, shutdown: CancellationToken) {
+    tokio::spawn(async move {
+        run_chain_watcher(state, chain_id, shutdown).await;
+    });
+}
+
+async fn run_chain_watcher(state: AppState, chain_id: u64, shutdown: CancellationToken) {
     let chain = match state.rpcs.chain(chain_id) {
-        Some(chain) => chain.clone(),
+        Some(chain) => chain,
         None => {
             warn!(%chain_id, "missing rpc chain for watcher");
             return;
         }
     };
+    let mut cursor = WatchCursor::default();
 
-    if state.config.watcher.use_websocket
+    if state.config.current().watcher.use_websocket
         && let Some(ws) = chain.ws.clone()
     {
-        match watch_ws(&state, chain_id, ws).await {
-            Ok(()) => return,
-            Err(err) => {
-                warn!(%chain_id, error = %err, "ws watcher failed, falling back to polling");
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                info!(%chain_id, "watcher stopped");
+                return;
             }
+            result = watch_ws(&state, chain_id, ws, &mut cursor) => match result {
+                Ok(()) => return,
+                Err(err) => {
+                    warn!(%chain_id, error = %err, "ws watcher failed, falling back to polling");
+                }
+            },
         }
     }
 
-    watch_poll(&state, chain_id, &chain).await;
+    watch_poll(&state, chain_id, &shutdown, &mut cursor).await;
 }
 
 async fn watch_ws(
     state: &AppState,
     chain_id: u64,
     ws: alloy::providers::DynProvider<tempo_alloy::TempoNetwork>,
+    cursor: &mut WatchCursor,
 ) -> anyhow::Result<()> {
     info!(%chain_id, "starting websocket watcher");
     let sub = ws.subscribe_blocks().await?;
     let mut stream = sub.into_stream();
 
     while let Some(_header) = stream.next().await {
-        if let Err(err) = process_tick(state, chain_id).await {
+        if let Err(err) = process_tick(state, chain_id, cursor).await {
             warn!(%chain_id, error = %err, "watcher tick failed");
         }
     }
@@ -63,47 +88,412 @@ async fn watch_ws(
     Err(anyhow::anyhow!("websocket stream ended"))
 }
 
-async fn watch_poll(state: &AppState, chain_id: u64, chain: &ChainRpc) {
+async fn watch_poll(
+    state: &AppState,
+    chain_id: u64,
+    shutdown: &CancellationToken,
+    cursor: &mut WatchCursor,
+) {
     info!(%chain_id, "starting polling watcher");
-    let mut interval =
-        tokio::time::interval(Duration::from_millis(state.config.watcher.poll_interval_ms));
+    let mut poll_interval_ms = state.config.current().watcher.poll_interval_ms;
+    let mut interval = tokio::time::interval(Duration::from_millis(poll_interval_ms));
 
     loop {
-        interval.tick().await;
-        if let Err(err) = process_tick_with_chain(state, chain_id, chain).await {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                info!(%chain_id, "watcher stopped");
+                return;
+            }
+            _ = interval.tick() => {}
+        }
+
+        let configured = state.config.current().watcher.poll_interval_ms;
+        if configured != poll_interval_ms {
+            poll_interval_ms = configured;
+            interval = tokio::time::interval(Duration::from_millis(poll_interval_ms));
+        }
+
+        if let Err(err) = process_tick(state, chain_id, cursor).await {
             warn!(%chain_id, error = %err, "polling watcher tick failed");
         }
     }
 }
 
-async fn process_tick(state: &AppState, chain_id: u64) -> anyhow::Result<()> {
+/// What the watcher remembers about a chain between ticks.
+#[derive(Default)]
+struct WatchCursor {
+    last_block: Option<u64>,
+    last_sweep: Option<Instant>,
+    /// Set once an endpoint reports `eth_getBlockReceipts` as unimplemented; blocks are then
+    /// matched by hash.
+    block_receipts_unsupported: bool,
+}
+
+/// Transactions resolved during one tick.
+#[derive(Default)]
+struct Settled {
+    ids: HashSet<i64>,
+    ladders: HashSet<Uuid>,
+    /// Next expected nonce for each `(sender, nonce_key)` the chain moved past this tick.
+    advanced: BTreeMap<(Vec<u8>, Vec<u8>), u64>,
+}
+
+impl Settled {
+    fn contains(&self, record: &TxRecord) -> bool {
+        self.ids.contains(&record.id)
+            || record
+                .ladder_id
+                .is_some_and(|ladder_id| self.ladders.contains(&ladder_id))
+    }
+
+    fn advance(&mut self, record: &TxRecord, next_nonce: u64) {
+        let entry = self
+            .advanced
+            .entry((record.sender.clone(), record.nonce_key.clone()))
+            .or_default();
+        *entry = (*entry).max(next_nonce);
+    }
+}
+
+async fn process_tick(
+    state: &AppState,
+    chain_id: u64,
+    cursor: &mut WatchCursor,
+) -> anyhow::Result<()> {
     let chain = state
         .rpcs
         .chain(chain_id)
         .ok_or_else(|| anyhow::anyhow!("missing rpc chain"))?;
-    process_tick_with_chain(state, chain_id, chain).await
+    process_tick_with_chain(state, chain_id, &chain, cursor).await
 }
 
+/// Matches new blocks against the active transactions, then runs the per-transaction
+/// reconciliation sweep when `watcher.reconcile_interval_ms` has passed.
 async fn process_tick_with_chain(
     state: &AppState,
     chain_id: u64,
     chain: &ChainRpc,
+    cursor: &mut WatchCursor,
 ) -> anyhow::Result<()> {
     let records = db::list_active_txs(&state.db, chain_id).await?;
-    if records.is_empty() {
+    let unconfirmed = db::list_unconfirmed_txs(&state.db, chain_id).await?;
+    if records.is_empty() && unconfirmed.is_empty() {
+        // Nothing to match; start from the tip with a full sweep once work shows up again.
+        cursor.last_block = None;
+        cursor.last_sweep = None;
         return Ok(());
     }
 
-    let provider = chain
-        .http
-        .first()
-        .ok_or_else(|| anyhow::anyhow!("missing provider"))?;
-    let latest_block = provider.get_block(BlockId::latest()).await?;
+    let latest_block = chain
+        .read(|provider| async move { provider.get_block(BlockId::latest()).await })
+        .await?;
+
+    if let Some(block) = &latest_block {
+        check_confirmations(state, chain_id, chain, block.header.number(), unconfirmed).await?;
+    }
+    if records.is_empty() {
+        cursor.last_block = None;
+        cursor.last_sweep = None;
+        return Ok(());
+    }
 
     let now = latest_block
+        .as_ref()
         .map(|block| block.header.timestamp_millis() as i64)
         .unwrap_or(Utc::now().timestamp_millis());
 
+    let mut pending = Vec::with_capacity(records.len());
+    for record in records {
+        if let Some(expires_at) = record.expires_at
+            && expires_at.timestamp_millis() <= now
+        {
+            if let Some(updated) = db::mark_expired(&state.db, record.id).await? {
+                telemetry::record_watcher_transition(chain_id, TxStatus::Expired);
+                events::publish(state, &updated).await;
+            }
+            continue;
+        }
+        pending.push(record);
+    }
+
+    let mut settled = Settled::default();
+    if let Some(block) = &latest_block {
+        scan_new_blocks(
+            state,
+            chain_id,
+            chain,
+            cursor,
+            block.header.number(),
+            &pending,
+            &mut settled,
+        )
+        .await?;
+    }
+
+    let reconcile_interval =
+        Duration::from_millis(state.config.current().watcher.reconcile_interval_ms);
+    if cursor
+        .last_sweep
+        .is_none_or(|last_sweep| last_sweep.elapsed() >= reconcile_interval)
+    {
+        cursor.last_sweep = Some(Instant::now());
+        pending.retain(|record| !settled.contains(record));
+        reconcile(state, chain_id, chain, pending, &mut settled).await?;
+    }
+
+    for ((sender, nonce_key), next_nonce) in settled.advanced {
+        scheduler::wake_nonce_successors(state, chain_id, &sender, &nonce_key, next_nonce).await?;
+    }
+
+    Ok(())
+}
+
+/// Looks for active transactions in the blocks mined since the last tick. At most
+/// `MAX_BLOCKS_PER_TICK` blocks are read; a longer gap is left to the reconciliation sweep.
+async fn scan_new_blocks(
+    state: &AppState,
+    chain_id: u64,
+    chain: &ChainRpc,
+    cursor: &mut WatchCursor,
+    latest: u64,
+    pending: &[TxRecord],
+    settled: &mut Settled,
+) -> anyhow::Result<()> {
+    let (blocks, skipped) = blocks_to_scan(cursor.last_block, latest);
+    if blocks.is_empty() {
+        return Ok(());
+    }
+    if skipped {
+        cursor.last_sweep = None;
+    }
+
+    let replaced = db::list_replaced_in_flight(&state.db, chain_id).await?;
+    let index: HashMap<B256, &TxRecord> = pending
+        .iter()
+        .chain(replaced.iter())
+        .filter(|record| record.tx_hash.len() == 32)
+        .map(|record| (B256::from_slice(&record.tx_hash), record))
+        .collect();
+
+    for number in blocks {
+        let Some(mined) = mined_in_block(chain, cursor, number, &index).await? else {
+            break;
+        };
+        for (record, receipt) in mined {
+            settle_mined(state, chain_id, record, receipt, settled).await?;
+        }
+        cursor.last_block = Some(number);
+    }
+
+    Ok(())
+}
+
+/// Blocks after `last_block` up to `latest`, capped at the newest `MAX_BLOCKS_PER_TICK`. The flag
+/// is set when older blocks had to be skipped.
+fn blocks_to_scan(last_block: Option<u64>, latest: u64) -> (RangeInclusive<u64>, bool) {
+    let first = match last_block {
+        Some(last) => last.saturating_add(1),
+        None => latest,
+    };
+    if first <= latest && latest - first >= MAX_BLOCKS_PER_TICK {
+        return (latest + 1 - MAX_BLOCKS_PER_TICK..=latest, true);
+    }
+    (first..=latest, false)
+}
+
+/// Receipts for the indexed transactions included in block `number`, or `None` if the endpoint
+/// doesn't have the block yet.
+async fn mined_in_block(
+    chain: &ChainRpc,
+    cursor: &mut WatchCursor,
+    number: u64,
+    index: &HashMap<B256, &TxRecord>,
+) -> anyhow::Result<Option<Vec<(TxRecord, TempoTransactionReceipt)>>> {
+    if !cursor.block_receipts_unsupported {
+        let receipts =
+            chain
+                .read(|provider| async move {
+                    provider.get_block_receipts(BlockId::number(number)).await
+                })
+                .await;
+        match receipts {
+            Ok(receipts) => {
+                return Ok(receipts.map(|receipts| {
+                    receipts
+                        .into_iter()
+                        .filter_map(|receipt| {
+                            index
+                                .get(&receipt.transaction_hash())
+                                .map(|record| ((*record).clone(), receipt))
+                        })
+                        .collect()
+                }));
+            }
+            Err(err) if is_method_unsupported(&err.to_string()) => {
+                warn!(
+                    chain_id = chain.chain_id,
+                    error = %err,
+                    "eth_getBlockReceipts unavailable, matching block transactions by hash"
+                );
+                cursor.block_receipts_unsupported = true;
+            }
+            Err(err) => {
+                warn!(
+                    chain_id = chain.chain_id,
+                    block = number,
+                    error = %err,
+                    "eth_getBlockReceipts failed, matching this block's transactions by hash"
+                );
+            }
+        }
+    }
+
+    let block = chain
+        .read(|provider| async move { provider.get_block(BlockId::number(number)).await })
+        .await?;
+    let Some(block) = block else {
+        return Ok(None);
+    };
+
+    let mut mined = Vec::new();
+    for hash in block.transactions.hashes() {
+        if let Some(record) = index.get(&hash)
+            && let Some(receipt) = fetch_receipt(chain, record).await?
+        {
+            mined.push(((*record).clone(), receipt));
+        }
+    }
+    Ok(Some(mined))
+}
+
+async fn settle_mined(
+    state: &AppState,
+    chain_id: u64,
+    record: TxRecord,
+    receipt: TempoTransactionReceipt,
+    settled: &mut Settled,
+) -> anyhow::Result<()> {
+    if settled.contains(&record) {
+        return Ok(());
+    }
+
+    let outcome = receipt_outcome(&receipt)?;
+    if let Some(updated) = db::mark_executed(&state.db, record.id, &outcome).await? {
+        telemetry::record_watcher_transition(chain_id, TxStatus::Executed);
+        telemetry::record_executed(&updated);
+        events::publish(state, &updated).await;
+        let was_replaced = record.status == TxStatus::Replaced.as_str();
+        fee_ladder::retire_siblings(state, &updated, was_replaced).await?;
+        settled.advance(&updated, updated.nonce.to_uint().saturating_add(1));
+    }
+    settled.ids.insert(record.id);
+    if let Some(ladder_id) = record.ladder_id {
+        settled.ladders.insert(ladder_id);
+    }
+    Ok(())
+}
+
+/// Confirms executed transactions once they are `watcher.confirmations` blocks deep. A receipt
+/// whose block is no longer canonical is looked up again, and the transaction goes back to the
+/// scheduler if it is no longer mined at all.
+async fn check_confirmations(
+    state: &AppState,
+    chain_id: u64,
+    chain: &ChainRpc,
+    latest: u64,
+    records: Vec<TxRecord>,
+) -> anyhow::Result<()> {
+    let depth = state.config.current().watcher.confirmations;
+    let mut canonical: HashMap<u64, Option<B256>> = HashMap::new();
+
+    for record in records {
+        let number = record
+            .receipt_block_number
+            .as_ref()
+            .map(|number| number.to_uint());
+        if let (Some(number), Some(hash)) = (number, record.receipt_block_hash.as_deref()) {
+            if number > latest {
+                // The endpoint serving this tick is behind the one that returned the receipt.
+                continue;
+            }
+            let canonical_hash = match canonical.get(&number) {
+                Some(canonical_hash) => *canonical_hash,
+                None => {
+                    let block = chain
+                        .read(|provider| async move {
+                            provider.get_block(BlockId::number(number)).await
+                        })
+                        .await?;
+                    let canonical_hash = block.map(|block| block.header.hash);
+                    canonical.insert(number, canonical_hash);
+                    canonical_hash
+                }
+            };
+            let Some(canonical_hash) = canonical_hash else {
+                continue;
+            };
+
+            if canonical_hash.as_slice() == hash {
+                if confirmation_depth_reached(number, latest, depth)
+                    && let Some(updated) = db::mark_confirmed(&state.db, record.id).await?
+                {
+                    telemetry::record_watcher_transition(chain_id, TxStatus::Confirmed);
+                    events::publish(state, &updated).await;
+                }
+                continue;
+            }
+        }
+
+        let outcome = match fetch_receipt(chain, &record).await? {
+            Some(receipt) => Some(receipt_outcome(&receipt)?),
+            None => None,
+        };
+        match outcome {
+            Some(outcome) if outcome.block.is_some() => {
+                if let Some(updated) = db::mark_executed(&state.db, record.id, &outcome).await? {
+                    info!(
+                        %chain_id,
+                        tx_hash = %bytes_to_hex(&updated.tx_hash),
+                        block = ?outcome.block.as_ref().map(|block| block.number),
+                        "transaction receipt moved to another block"
+                    );
+                    events::publish(state, &updated).await;
+                }
+            }
+            _ => {
+                if let Some(updated) = db::mark_reorged(&state.db, record.id).await? {
+                    warn!(
+                        %chain_id,
+                        tx_hash = %bytes_to_hex(&updated.tx_hash),
+                        block = ?number,
+                        "transaction reorged out, rescheduling"
+                    );
+                    telemetry::record_watcher_transition(chain_id, TxStatus::RetryScheduled);
+                    events::publish(state, &updated).await;
+                    scheduler::schedule_records(state, std::slice::from_ref(&updated)).await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a receipt in block `number` has `depth` blocks, counting its own, as of `latest`.
+fn confirmation_depth_reached(number: u64, latest: u64, depth: u64) -> bool {
+    latest.saturating_sub(number).saturating_add(1) >= depth
+}
+
+/// Per-transaction pass: checks each pending transaction's receipt and its sender's current
+/// nonce. Catches anything the block scan missed, such as transactions mined before they were
+/// submitted or while the watcher was behind.
+async fn reconcile(
+    state: &AppState,
+    chain_id: u64,
+    chain: &ChainRpc,
+    records: Vec<TxRecord>,
+    settled: &mut Settled,
+) -> anyhow::Result<()> {
     let mut grouped: BTreeMap<(Vec<u8>, Vec<u8>), Vec<TxRecord>> = BTreeMap::new();
     for record in records {
         grouped
@@ -112,28 +502,40 @@ async fn process_tick_with_chain(
             .push(record);
     }
 
+    // Receipts are looked up once per fee ladder; a settled ladder's other rungs are skipped.
+    let mut checked_ladders = HashSet::new();
     for ((sender, nonce_key_bytes), records) in grouped {
         let sender_addr = parse_address(&sender)?;
-        let current_nonce = fetch_current_nonce(chain, sender_addr, &nonce_key_bytes).await?;
+        let Some(current_nonce) = fetch_current_nonce(chain, sender_addr, &nonce_key_bytes).await?
+        else {
+            continue;
+        };
 
-        if let Some(current_nonce) = current_nonce {
-            for record in records {
-                if let Some(expires_at) = record.expires_at
-                    && expires_at.timestamp_millis() <= now
-                {
-                    db::mark_expired(&state.db, record.id).await?;
-                    continue;
-                }
+        for record in records {
+            if settled.contains(&record) {
+                continue;
+            }
 
-                if let Some(receipt) = fetch_receipt(chain, &record).await? {
-                    let receipt_json = serde_json::to_value(receipt)?;
-                    db::mark_executed(&state.db, record.id, receipt_json).await?;
-                    continue;
+            let check_receipt = record
+                .ladder_id
+                .is_none_or(|ladder_id| checked_ladders.insert(ladder_id));
+            if check_receipt
+                && let Some((mined, receipt)) =
+                    fee_ladder::find_receipt(state, chain, &record).await?
+            {
+                settle_mined(state, chain_id, mined, receipt, settled).await?;
+                if let Some(ladder_id) = record.ladder_id {
+                    settled.ladders.insert(ladder_id);
                 }
+                continue;
+            }
 
-                if current_nonce > record.nonce.to_uint() {
-                    db::mark_stale_by_nonce(&state.db, record.id).await?;
-                }
+            if current_nonce > record.nonce.to_uint()
+                && let Some(updated) = db::mark_stale_by_nonce(&state.db, record.id).await?
+            {
+                telemetry::record_watcher_transition(chain_id, TxStatus::StaleByNonce);
+                events::publish(state, &updated).await;
+                settled.advance(&updated, current_nonce);
             }
         }
     }
@@ -141,38 +543,8 @@ async fn process_tick_with_chain(
     Ok(())
 }
 
-async fn fetch_current_nonce(
-    chain: &ChainRpc,
-    sender: alloy::primitives::Address,
-    nonce_key_bytes: &[u8],
-) -> anyhow::Result<Option<u64>> {
-    let nonce_key = u256_from_bytes(nonce_key_bytes)?;
-    if nonce_key.is_zero() {
-        let provider = chain
-            .http
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("missing provider"))?;
-        let nonce = provider.get_transaction_count(sender).await?;
-        return Ok(Some(nonce));
-    }
-
-    let call = tempo_alloy::contracts::precompiles::INonce::getNonceCall {
-        account: sender,
-        nonceKey: nonce_key,
-    };
-    let mut req = tempo_alloy::rpc::TempoTransactionRequest::default();
-    req.set_kind(alloy::primitives::TxKind::Call(nonce_precompile_address()));
-    req.set_call(&call);
-
-    let provider = chain
-        .http
-        .first()
-        .ok_or_else(|| anyhow::anyhow!("missing provider"))?;
-    let output = provider
-        .call(req)
-        .decode_resp::<tempo_alloy::contracts::precompiles::INonce::getNonceCall>()
-        .await??;
-    Ok(Some(output))
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
 }
 
 fn parse_address(bytes: &[u8]) -> anyhow::Result<alloy::primitives::Address> {
@@ -184,18 +556,54 @@ fn parse_address(bytes: &[u8]) -> anyhow::Result<alloy::primitives::Address> {
     Ok(alloy::primitives::Address::from(data))
 }
 
-fn u256_from_bytes(bytes: &[u8]) -> anyhow::Result<alloy::primitives::U256> {
-    if bytes.len() > 32 {
-        anyhow::bail!("nonce_key too large");
-    }
-    let mut buf = [0u8; 32];
-    let offset = 32 - bytes.len();
-    buf[offset..].copy_from_slice(bytes);
-    Ok(alloy::primitives::U256::from_be_slice(&buf))
+/// Whether an RPC error means the method isn't implemented, rather than a transient failure.
+fn is_method_unsupported(message: &str) -> bool {
+    let msg = message.to_lowercase();
+    msg.contains("-32601")
+        || msg.contains("method not found")
+        || msg.contains("does not exist")
+        || msg.contains("not supported")
+        || msg.contains("unsupported")
 }
 
-fn nonce_precompile_address() -> alloy::primitives::Address {
-    alloy::primitives::Address::from_slice(
-        &hex::decode("4e4f4e4345000000000000000000000000000000").expect("valid precompile"),
-    )
+#[cfg(test)]
+mod tests {
+    use super::{
+        MAX_BLOCKS_PER_TICK, blocks_to_scan, confirmation_depth_reached, is_method_unsupported,
+    };
+
+    #[test]
+    fn only_unimplemented_methods_are_unsupported() {
+        assert!(is_method_unsupported(
+            "server returned an error response: error code -32601: the method eth_getBlockReceipts does not exist/is not available"
+        ));
+        assert!(is_method_unsupported("Method not found"));
+        assert!(!is_method_unsupported(
+            "error sending request for url (http://127.0.0.1:8545/)"
+        ));
+        assert!(!is_method_unsupported("request timed out"));
+    }
+
+    #[test]
+    fn blocks_to_scan_resumes_after_last_block() {
+        assert_eq!(blocks_to_scan(None, 100), (100..=100, false));
+        assert_eq!(blocks_to_scan(Some(97), 100), (98..=100, false));
+        assert!(blocks_to_scan(Some(100), 100).0.is_empty());
+    }
+
+    #[test]
+    fn blocks_to_scan_caps_long_gaps() {
+        let (blocks, skipped) = blocks_to_scan(Some(0), 1000);
+        assert!(skipped);
+        assert_eq!(*blocks.end(), 1000);
+        assert_eq!(blocks.count() as u64, MAX_BLOCKS_PER_TICK);
+    }
+
+    #[test]
+    fn confirmation_depth_counts_the_receipt_block() {
+        assert!(!confirmation_depth_reached(100, 104, 6));
+        assert!(confirmation_depth_reached(100, 105, 6));
+        assert!(confirmation_depth_reached(100, 100, 1));
+        assert!(confirmation_depth_reached(100, 100, 0));
+    }
 }
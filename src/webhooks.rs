@@ -0,0 +1,330 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::Sha256;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::ConfigHandle;
+use crate::db;
+use crate::models::{WebhookDeliveryJob, WebhookDeliveryStatus};
+use crate::scheduler::retry_backoff_ms;
+use crate::state::AppState;
+
+pub const EVENT_ID_HEADER: &str = "watchtower-event-id";
+pub const TIMESTAMP_HEADER: &str = "watchtower-timestamp";
+pub const SIGNATURE_HEADER: &str = "watchtower-signature";
+
+pub fn start(state: AppState) {
+    tokio::spawn(async move {
+        run_delivery_loop(state).await;
+    });
+}
+
+/// Generates the shared secret returned once when a webhook is registered.
+pub fn generate_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// Signs `"{timestamp}.{body}"` with HMAC-SHA256 and returns the `v1=<hex>` header value.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Checks that `url` may receive webhooks: an http(s) URL whose host is in
+/// `webhooks.allowed_hosts` or resolves to public addresses only.
+pub async fn check_target(url: &str, allowed_hosts: &[String]) -> Result<(), &'static str> {
+    let url = reqwest::Url::parse(url).map_err(|_| "url must be an http(s) URL")?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("url must be an http(s) URL");
+    }
+    let host = url.host_str().ok_or("url must be an http(s) URL")?;
+    if host_allowed(host, allowed_hosts) {
+        return Ok(());
+    }
+
+    let ips: Vec<IpAddr> = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => tokio::net::lookup_host((host, url.port_or_known_default().unwrap_or(80)))
+            .await
+            .map_err(|_| "url host could not be resolved")?
+            .map(|addr| addr.ip())
+            .collect(),
+    };
+    if ips.is_empty() || !ips.into_iter().all(is_public) {
+        return Err("url must not target a loopback, private or link-local address");
+    }
+    Ok(())
+}
+
+fn host_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    let host = host.trim_matches(['[', ']']);
+    allowed_hosts
+        .iter()
+        .any(|allowed| allowed.trim_matches(['[', ']']).eq_ignore_ascii_case(host))
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                // Shared address space (100.64.0.0/10).
+                || (a == 100 && b & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// Drops addresses a webhook host may not reach when connecting, so a host that passed
+/// `check_target` cannot rebind to an internal address afterwards.
+struct PublicResolver {
+    config: ConfigHandle,
+}
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        let allowed = host_allowed(&host, &self.config.current().webhooks.allowed_hosts);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| allowed || is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{host} resolves to no public address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn run_delivery_loop(state: AppState) {
+    let config = state.config.current().webhooks.clone();
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_millis(config.timeout_ms))
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver {
+            config: state.config.clone(),
+        }))
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            warn!(error = %err, "failed to build webhook http client");
+            return;
+        }
+    };
+    let lease_owner = format!("webhooks:{}", Uuid::new_v4());
+    let mut interval = tokio::time::interval(Duration::from_millis(config.poll_interval_ms));
+
+    loop {
+        interval.tick().await;
+
+        let now = Utc::now();
+        let lease_until = now + chrono::Duration::seconds(config.lease_ttl_seconds);
+        let jobs = match db::lease_due_webhook_deliveries(
+            &state.db,
+            now,
+            &lease_owner,
+            lease_until,
+            config.batch_size,
+        )
+        .await
+        {
+            Ok(jobs) => jobs,
+            Err(err) => {
+                warn!(error = %err, "failed to lease webhook deliveries");
+                continue;
+            }
+        };
+
+        let mut tasks = JoinSet::new();
+        for job in jobs {
+            let state = state.clone();
+            let client = client.clone();
+            let lease_owner = lease_owner.clone();
+            tasks.spawn(async move {
+                if let Err(err) = deliver(&state, &client, &lease_owner, job).await {
+                    warn!(error = %err, "failed to record webhook delivery");
+                }
+            });
+        }
+        while tasks.join_next().await.is_some() {}
+    }
+}
+
+async fn deliver(
+    state: &AppState,
+    client: &reqwest::Client,
+    lease_owner: &str,
+    job: WebhookDeliveryJob,
+) -> anyhow::Result<()> {
+    let config = state.config.current().webhooks.clone();
+    let body = serde_json::to_vec(&job.payload)?;
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_payload(&job.secret, timestamp, &body);
+    let attempts = job.attempts.saturating_add(1);
+
+    let result = match check_target(&job.url, &config.allowed_hosts).await {
+        Ok(()) => client
+            .post(&job.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_ID_HEADER, job.event_id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| err.to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+
+    let (error, status_code) = match result {
+        Ok(response) if response.status().is_success() => {
+            let status_code = i32::from(response.status().as_u16());
+            db::mark_webhook_delivered(&state.db, job.id, lease_owner, attempts, status_code)
+                .await?;
+            info!(
+                delivery_id = job.id,
+                event_id = %job.event_id,
+                attempts,
+                "webhook delivered"
+            );
+            return Ok(());
+        }
+        Ok(response) => (
+            format!("receiver responded with {}", response.status()),
+            Some(i32::from(response.status().as_u16())),
+        ),
+        Err(err) => (err, None),
+    };
+
+    let (status, next_attempt_at) = if attempts >= config.max_attempts {
+        (WebhookDeliveryStatus::Failed, None)
+    } else {
+        let delay_ms = retry_backoff_ms(attempts as u64, config.retry_min_ms, config.retry_max_ms);
+        (
+            WebhookDeliveryStatus::Pending,
+            Some(Utc::now() + chrono::Duration::milliseconds(delay_ms as i64)),
+        )
+    };
+    warn!(
+        delivery_id = job.id,
+        event_id = %job.event_id,
+        attempts,
+        status = status.as_str(),
+        error = %error,
+        "webhook delivery failed"
+    );
+    db::reschedule_webhook_delivery(
+        &state.db,
+        job.id,
+        lease_owner,
+        status,
+        next_attempt_at,
+        attempts,
+        &error,
+        status_code,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_target, generate_secret, is_public, sign_payload};
+
+    #[test]
+    fn sign_payload_matches_known_vector() {
+        // echo -n '1700000000.{"ok":true}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            sign_payload("secret", 1_700_000_000, br#"{"ok":true}"#),
+            "v1=c1afc7c2df3db0690d7d75954610ed1a1d959ce96355ccb8c0a8bc09fd0cfc27"
+        );
+    }
+
+    #[test]
+    fn generated_secrets_are_unique() {
+        let a = generate_secret();
+        let b = generate_secret();
+        assert!(a.starts_with("whsec_"));
+        assert_eq!(a.len(), "whsec_".len() + 64);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+        assert!(is_public("93.184.216.34".parse().unwrap()));
+        assert!(is_public("2606:4700::1111".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn check_target_honours_allowed_hosts() {
+        let allowed = vec!["127.0.0.1".to_string()];
+        assert!(
+            check_target("http://127.0.0.1:8080/hook", &allowed)
+                .await
+                .is_ok()
+        );
+        assert!(
+            check_target("http://127.0.0.1:8080/hook", &[])
+                .await
+                .is_err()
+        );
+        assert!(
+            check_target("http://169.254.169.254/latest", &allowed)
+                .await
+                .is_err()
+        );
+        assert!(check_target("http://[::1]/hook", &[]).await.is_err());
+        assert!(check_target("ftp://93.184.216.34/", &[]).await.is_err());
+        assert!(check_target("https://93.184.216.34/", &[]).await.is_ok());
+    }
+}
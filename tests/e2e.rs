@@ -9,22 +9,34 @@ use alloy::signers::local::PrivateKeySigner;
 use axum::routing::post;
 use axum::{Json, Router};
 use chrono::Utc;
+use reqwest::header::HeaderMap;
 use serde_json::Value;
 use tempo_alloy::primitives::transaction::{Call, PrimitiveSignature};
 use tempo_alloy::primitives::{AASigned, TempoSignature, TempoTransaction};
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 use tokio::time::timeout;
+use uuid::Uuid;
 
 use temprano_watchtower::api;
+use temprano_watchtower::api_keys;
+use temprano_watchtower::auth::{self, Authorization, SignedAction};
 use temprano_watchtower::config::{
-    ApiConfig, BroadcasterConfig, Config, DatabaseConfig, RedisConfig, RpcConfig, SchedulerConfig,
-    ServerConfig, WatcherConfig,
+    ApiConfig, BroadcasterConfig, Config, ConfigHandle, DatabaseConfig, RedisConfig,
+    RetentionConfig, RetentionPolicy, RpcConfig, RpcHealthConfig, SchedulerConfig, ServerConfig,
+    WatcherConfig, WebhookConfig,
 };
 use temprano_watchtower::db;
+use temprano_watchtower::events::{self, EventBus};
+use temprano_watchtower::models::{NewApiKey, ReceiptBlock, ReceiptOutcome};
+use temprano_watchtower::retention;
 use temprano_watchtower::rpc::RpcManager;
-use temprano_watchtower::scheduler;
+use temprano_watchtower::scheduler::{self, QueueDrift, Wakeups};
+use temprano_watchtower::shutdown::{self, Shutdown};
 use temprano_watchtower::state::AppState;
+use temprano_watchtower::telemetry;
+use temprano_watchtower::watcher;
+use temprano_watchtower::webhooks;
 
 static E2E_LOCK: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
 
@@ -34,6 +46,13 @@ const CHAIN_ID: u64 = 42431;
 struct RpcState {
     seen_raw: Arc<Mutex<Vec<String>>>,
     current_nonce: Arc<AtomicU64>,
+    /// Hashes the fake node reports a receipt for.
+    mined: Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(Clone, Default)]
+struct WebhookReceiverState {
+    deliveries: Arc<Mutex<Vec<(axum::http::HeaderMap, String)>>>,
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -46,6 +65,71 @@ async fn e2e_signed_tx_is_broadcast() -> anyhow::Result<()> {
 
     wait_for_raw(&rpc_state, &raw_tx).await?;
 
+    let attempts = timeout(Duration::from_secs(5), async {
+        loop {
+            let attempts = list_broadcast_attempts(&api_addr, &raw_tx).await?;
+            if !attempts.is_empty() {
+                return anyhow::Ok(attempts);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out waiting for broadcast attempts"))??;
+    let first = &attempts[0];
+    assert_eq!(first.get("attempt").and_then(Value::as_i64), Some(1));
+    assert_eq!(
+        first.get("outcome").and_then(Value::as_str),
+        Some("accepted")
+    );
+    assert!(
+        first
+            .get("endpoint")
+            .and_then(Value::as_str)
+            .is_some_and(|endpoint| endpoint.starts_with("http://"))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_metrics_reports_broadcasts_and_queue_depth() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state) = setup_e2e().await?;
+    let raw_tx = build_signed_tx()?;
+
+    send_signed_tx(&api_addr, &raw_tx).await?;
+    wait_for_raw(&rpc_state, &raw_tx).await?;
+
+    let body = timeout(Duration::from_secs(5), async {
+        loop {
+            let resp = reqwest::get(format!("http://{api_addr}/metrics")).await?;
+            assert!(resp.status().is_success());
+            let body = resp.text().await?;
+            if body.contains("watchtower_broadcasts_total") {
+                return anyhow::Ok(body);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out waiting for broadcast metrics"))??;
+
+    let chain_label = format!("chain_id=\"{CHAIN_ID}\"");
+    for series in [
+        "watchtower_txs_ingested_total",
+        "watchtower_broadcasts_total",
+        "watchtower_queue_depth",
+        "watchtower_txs{",
+        "watchtower_rpc_request_duration_seconds_bucket",
+    ] {
+        assert!(
+            body.lines()
+                .any(|line| line.starts_with(series) && line.contains(&chain_label)),
+            "missing {series} for chain {CHAIN_ID}"
+        );
+    }
+
     Ok(())
 }
 
@@ -94,14 +178,392 @@ async fn e2e_cancel_group_prevents_broadcast() -> anyhow::Result<()> {
 
     send_signed_tx(&api_addr, &raw_tx).await?;
 
-    let auth_header = build_cancel_auth(&signer, group_id)?;
-    cancel_group(&api_addr, signer.address(), group_id, &auth_header).await?;
+    let auth = build_cancel_auth(&signer, group_id)?;
+    cancel_group(&api_addr, signer.address(), group_id, auth).await?;
 
     assert_not_broadcast_within(&rpc_state, &raw_tx, Duration::from_secs(5)).await?;
 
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_cancel_authorization_is_single_use() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state, state) = setup_e2e_with_state().await?;
+    let signer = PrivateKeySigner::random();
+    let nonce_key = build_group_nonce_key(1, 11);
+    let group_id = group_id_from_nonce_key(nonce_key);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let raw_tx = build_group_signed_tx_with_valid_after(&signer, nonce_key, Some(now + 30))?;
+    send_signed_tx(&api_addr, &raw_tx).await?;
+
+    let legacy = build_legacy_cancel_auth(&signer, group_id)?;
+    let resp = post_cancel_group(&api_addr, signer.address(), group_id, legacy.clone()).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let auth = build_cancel_auth(&signer, group_id)?;
+    cancel_group(&api_addr, signer.address(), group_id, auth.clone()).await?;
+    let resp = post_cancel_group(&api_addr, signer.address(), group_id, auth).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let mut config = (*state.config.current()).clone();
+    config.api.legacy_cancel_signatures = true;
+    state.config.replace(config);
+    let raw_tx = build_group_signed_tx(&signer, nonce_key, 1, Some(now + 30))?;
+    send_signed_tx(&api_addr, &raw_tx).await?;
+    let resp = post_cancel_group(&api_addr, signer.address(), group_id, legacy).await?;
+    assert!(resp.status().is_success());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_paused_group_waits_until_resumed() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let nonce_key = build_group_nonce_key(1, 12);
+    let group_id = group_id_from_nonce_key(nonce_key);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let raw_tx = build_group_signed_tx_with_valid_after(&signer, nonce_key, Some(now + 2))?;
+    let tx_hash = json_hex_hash(&raw_tx)
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+        .to_string();
+    send_signed_tx(&api_addr, &raw_tx).await?;
+
+    let legacy = build_legacy_cancel_auth(&signer, group_id)?;
+    let resp = post_group_action(&api_addr, signer.address(), group_id, "pause", legacy).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let auth = build_typed_auth(&signer, SignedAction::PauseGroup(&group_id))?;
+    let resp = post_group_action(&api_addr, signer.address(), group_id, "pause", auth).await?;
+    assert!(resp.status().is_success());
+    let body: Value = resp.json().await?;
+    assert_eq!(body.get("paused").and_then(Value::as_u64), Some(1));
+
+    assert_not_broadcast_within(&rpc_state, &raw_tx, Duration::from_secs(4)).await?;
+    let tx = get_transaction(&api_addr, &tx_hash).await?;
+    assert_eq!(tx.get("status").and_then(Value::as_str), Some("paused"));
+
+    let replacement = build_group_signed_tx_with_valid_after(&signer, nonce_key, Some(now + 3))?;
+    let resp = submit_replacement(&api_addr, &replacement).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let auth = build_typed_auth(&signer, SignedAction::ResumeGroup(&group_id))?;
+    let resp = post_group_action(&api_addr, signer.address(), group_id, "resume", auth).await?;
+    assert!(resp.status().is_success());
+    let body: Value = resp.json().await?;
+    assert_eq!(body.get("resumed").and_then(Value::as_u64), Some(1));
+
+    wait_for_raw_with_deadline(&rpc_state, &raw_tx, Duration::from_secs(6)).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_cancel_group_from_nonce_keeps_earlier_members() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let nonce_key = build_group_nonce_key(1, 13);
+    let group_id = group_id_from_nonce_key(nonce_key);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut tx_hashes = Vec::new();
+    for nonce in 0..3 {
+        let raw_tx = build_group_signed_tx(&signer, nonce_key, nonce, Some(now + 30))?;
+        send_signed_tx(&api_addr, &raw_tx).await?;
+        tx_hashes.push(json_hex_hash(&raw_tx));
+    }
+
+    let group = get_group(&api_addr, signer.address(), group_id, "fromNonce=1").await?;
+    assert_eq!(
+        group.pointer("/cancelPlan/nonces"),
+        Some(&serde_json::json!([1, 2]))
+    );
+
+    let whole_group = build_cancel_auth(&signer, group_id)?;
+    let resp =
+        post_cancel_group_nonces(&api_addr, signer.address(), group_id, 1, whole_group).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let auth = build_typed_auth(
+        &signer,
+        SignedAction::CancelGroupNonces {
+            group_id: &group_id,
+            from_nonce: 1,
+            to_nonce: u64::MAX,
+        },
+    )?;
+    let resp = post_cancel_group_nonces(&api_addr, signer.address(), group_id, 1, auth).await?;
+    assert!(resp.status().is_success());
+    let body: Value = resp.json().await?;
+    assert_eq!(body.get("canceled").and_then(Value::as_u64), Some(2));
+    assert_eq!(
+        body.get("txHashes"),
+        Some(&Value::from(tx_hashes[1..].to_vec()))
+    );
+
+    let statuses = [
+        (&tx_hashes[0], "queued"),
+        (&tx_hashes[1], "canceled_locally"),
+        (&tx_hashes[2], "canceled_locally"),
+    ];
+    for (tx_hash, status) in statuses {
+        let tx_hash = tx_hash.as_str().unwrap_or_default();
+        let tx = get_transaction(&api_addr, tx_hash).await?;
+        assert_eq!(tx.get("status").and_then(Value::as_str), Some(status));
+    }
+
+    let auth = build_cancel_auth(&signer, group_id)?;
+    let resp = post_cancel_group(&api_addr, signer.address(), group_id, auth).await?;
+    assert!(resp.status().is_success());
+    let body: Value = resp.json().await?;
+    assert_eq!(body.get("canceled").and_then(Value::as_u64), Some(1));
+    assert_eq!(
+        body.get("txHashes"),
+        Some(&Value::from(tx_hashes[..1].to_vec()))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_cancel_group_delivers_signed_webhook() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let (receiver_addr, receiver_state) = start_webhook_receiver().await?;
+    let signer = PrivateKeySigner::random();
+    let sender_hex = format!("0x{}", hex::encode(signer.address().as_slice()));
+    let nonce_key = build_group_nonce_key(1, 11);
+    let group_id = group_id_from_nonce_key(nonce_key);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let raw_tx = build_group_signed_tx_with_valid_after(&signer, nonce_key, Some(now + 30))?;
+
+    let url = format!("http://{receiver_addr}/");
+    let auth = build_typed_auth(
+        &signer,
+        SignedAction::RegisterWebhook {
+            group_id: None,
+            url: &url,
+        },
+    )?;
+    let resp = post_webhook(
+        &api_addr,
+        serde_json::json!({
+            "url": url,
+            "chainId": CHAIN_ID,
+            "sender": sender_hex,
+        }),
+        auth,
+    )
+    .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::CREATED);
+    let webhook: Value = resp.json().await?;
+    let secret = webhook
+        .get("secret")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("missing webhook secret"))?
+        .to_string();
+
+    send_signed_tx(&api_addr, &raw_tx).await?;
+    let auth = build_cancel_auth(&signer, group_id)?;
+    cancel_group(&api_addr, signer.address(), group_id, auth).await?;
+
+    let (headers, body) = timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(delivery) = receiver_state.deliveries.lock().await.first().cloned() {
+                return delivery;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out waiting for webhook delivery"))?;
+
+    let timestamp: i64 = headers
+        .get(webhooks::TIMESTAMP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("missing timestamp header"))?;
+    let signature = headers
+        .get(webhooks::SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    assert_eq!(
+        signature,
+        webhooks::sign_payload(&secret, timestamp, body.as_bytes())
+    );
+
+    let event: Value = serde_json::from_str(&body)?;
+    assert_eq!(
+        event.get("type").and_then(Value::as_str),
+        Some("transaction.canceled_locally")
+    );
+    assert_eq!(
+        event.get("txHash").and_then(Value::as_str),
+        json_hex_hash(&raw_tx).as_str()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_webhook_registration_is_authorized_and_public() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let url = "http://127.0.0.1:9/hook";
+    let body = serde_json::json!({
+        "url": url,
+        "chainId": CHAIN_ID,
+        "sender": format!("{:#x}", signer.address()),
+    });
+    let action = SignedAction::RegisterWebhook {
+        group_id: None,
+        url,
+    };
+
+    let resp = post_webhook(&api_addr, body.clone(), HeaderMap::new()).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let stranger = build_typed_auth(&PrivateKeySigner::random(), action)?;
+    let resp = post_webhook(&api_addr, body.clone(), stranger).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let other_url = build_typed_auth(
+        &signer,
+        SignedAction::RegisterWebhook {
+            group_id: None,
+            url: "http://127.0.0.1:10/hook",
+        },
+    )?;
+    let resp = post_webhook(&api_addr, body.clone(), other_url).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let resp = post_webhook(&api_addr, body, build_typed_auth(&signer, action)?).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::CREATED);
+    let webhook: Value = resp.json().await?;
+    let id: Uuid = webhook
+        .get("id")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("missing webhook id"))?
+        .parse()?;
+    let webhook_url = format!("http://{api_addr}/v1/webhooks/{id}");
+    let client = reqwest::Client::new();
+
+    let resp = client.get(&webhook_url).send().await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let resp = client
+        .get(&webhook_url)
+        .headers(build_typed_auth(
+            &signer,
+            SignedAction::GetWebhook(id.as_bytes()),
+        )?)
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    let resp = client.delete(&webhook_url).send().await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let stranger = build_typed_auth(
+        &PrivateKeySigner::random(),
+        SignedAction::DeleteWebhook(id.as_bytes()),
+    )?;
+    let resp = client.delete(&webhook_url).headers(stranger).send().await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let resp = client
+        .delete(&webhook_url)
+        .headers(build_typed_auth(
+            &signer,
+            SignedAction::DeleteWebhook(id.as_bytes()),
+        )?)
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::NO_CONTENT);
+
+    for internal in [
+        "http://169.254.169.254/latest",
+        "http://10.0.0.1/",
+        "http://[::1]/",
+    ] {
+        let resp = post_webhook(
+            &api_addr,
+            serde_json::json!({
+                "url": internal,
+                "chainId": CHAIN_ID,
+                "txHash": format!("0x{}", "ab".repeat(32)),
+            }),
+            HeaderMap::new(),
+        )
+        .await?;
+        assert_eq!(
+            resp.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{internal}"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_event_stream_reports_group_cancel() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let sender_hex = format!("0x{}", hex::encode(signer.address().as_slice()));
+    let nonce_key = build_group_nonce_key(1, 11);
+    let group_id = group_id_from_nonce_key(nonce_key);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let raw_tx = build_group_signed_tx_with_valid_after(&signer, nonce_key, Some(now + 30))?;
+
+    let client = reqwest::Client::new();
+    let mut stream = client
+        .get(format!(
+            "http://{api_addr}/v1/events?chainId={CHAIN_ID}&sender={sender_hex}"
+        ))
+        .send()
+        .await?;
+    assert!(stream.status().is_success());
+    // Give the redis subscriber a moment to attach before the first publish.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    send_signed_tx(&api_addr, &raw_tx).await?;
+    let auth = build_cancel_auth(&signer, group_id)?;
+    cancel_group(&api_addr, signer.address(), group_id, auth).await?;
+
+    let body = timeout(Duration::from_secs(5), async {
+        let mut body = String::new();
+        while let Some(chunk) = stream.chunk().await? {
+            body.push_str(&String::from_utf8_lossy(&chunk));
+            if body.contains("event: transaction.canceled_locally") {
+                break;
+            }
+        }
+        anyhow::Ok(body)
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out waiting for stream event"))??;
+
+    let data = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .next_back()
+        .ok_or_else(|| anyhow::anyhow!("missing event data"))?;
+    let event: Value = serde_json::from_str(data)?;
+    assert_eq!(
+        event.get("status").and_then(Value::as_str),
+        Some("canceled_locally")
+    );
+    assert_eq!(
+        event.get("txHash").and_then(Value::as_str),
+        json_hex_hash(&raw_tx).as_str()
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn e2e_cancel_single_tx_marks_stale_by_nonce() -> anyhow::Result<()> {
     let _guard = acquire_e2e_lock().await;
@@ -125,6 +587,61 @@ async fn e2e_cancel_single_tx_marks_stale_by_nonce() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_signed_cancel_stops_waiting_tx() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_state().await?;
+    let signer = PrivateKeySigner::random();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let raw_tx = build_group_signed_tx(&signer, U256::ZERO, 0, Some(now + 2))?;
+    let tx_hash = json_hex_hash(&raw_tx)
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+        .to_string();
+
+    send_signed_tx(&api_addr, &raw_tx).await?;
+
+    let stranger = PrivateKeySigner::random();
+    let resp = delete_transaction(
+        &api_addr,
+        &tx_hash,
+        build_tx_cancel_auth(&stranger, &tx_hash)?,
+    )
+    .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let resp = delete_transaction(
+        &api_addr,
+        &tx_hash,
+        build_tx_cancel_auth(&signer, &tx_hash)?,
+    )
+    .await?;
+    assert!(resp.status().is_success());
+    let body: Value = resp.json().await?;
+    assert_eq!(
+        body.get("status").and_then(Value::as_str),
+        Some("canceled_locally")
+    );
+
+    let has_raw_tx: bool =
+        sqlx::query_scalar("SELECT raw_tx IS NOT NULL FROM txs WHERE tx_hash = decode($1, 'hex')")
+            .bind(tx_hash.trim_start_matches("0x"))
+            .fetch_one(&state.db)
+            .await?;
+    assert!(!has_raw_tx);
+    assert_not_broadcast_within(&rpc_state, &raw_tx, Duration::from_secs(4)).await?;
+
+    let resp = delete_transaction(
+        &api_addr,
+        &tx_hash,
+        build_tx_cancel_auth(&signer, &tx_hash)?,
+    )
+    .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn e2e_list_groups_includes_start_end_and_active_filter() -> anyhow::Result<()> {
     let _guard = acquire_e2e_lock().await;
@@ -187,52 +704,1076 @@ async fn e2e_list_groups_includes_start_end_and_active_filter() -> anyhow::Resul
 
     assert!(find_group(&groups_all, &group_two_hex).is_some());
 
-    tokio::time::sleep(Duration::from_secs(2)).await;
-    let groups_active = list_groups(&api_addr, &sender_hex, "chainId=42431&active=true").await?;
-    let now_ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
-    for group in &groups_active {
-        let end_at = group
-            .get("endAt")
-            .and_then(Value::as_i64)
-            .unwrap_or_default();
-        assert!(end_at > now_ts, "active group has endAt <= now");
+    let client = reqwest::Client::new();
+    let page_url = format!(
+        "http://{api_addr}/v1/groups?sender={sender_hex}&chainId={CHAIN_ID}&sort=created_at&order=asc&limit=1"
+    );
+    let resp = client.get(&page_url).send().await?;
+    assert!(resp.status().is_success());
+    let (first_page, cursor) = page_items(resp.json().await?, "groups")?;
+    assert!(find_group(&first_page, &group_one_hex).is_some());
+    let cursor = cursor.ok_or_else(|| anyhow::anyhow!("missing groups nextCursor"))?;
+    let resp = client
+        .get(format!("{page_url}&cursor={cursor}"))
+        .send()
+        .await?;
+    assert!(resp.status().is_success());
+    let (second_page, cursor) = page_items(resp.json().await?, "groups")?;
+    assert!(find_group(&second_page, &group_two_hex).is_some());
+    assert!(cursor.is_none());
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let groups_active = list_groups(&api_addr, &sender_hex, "chainId=42431&active=true").await?;
+    let now_ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    for group in &groups_active {
+        let end_at = group
+            .get("endAt")
+            .and_then(Value::as_i64)
+            .unwrap_or_default();
+        assert!(end_at > now_ts, "active group has endAt <= now");
+    }
+    assert!(find_group(&groups_active, &group_one_hex).is_some());
+    assert!(find_group(&groups_active, &group_two_hex).is_none());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_list_transactions_ungrouped_filter() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let nonce_key = build_group_nonce_key(1, 11);
+
+    let raw_grouped = build_group_signed_tx_with_valid_after(&signer, nonce_key, None)?;
+    let raw_ungrouped = build_signed_tx()?;
+
+    send_signed_tx(&api_addr, &raw_grouped).await?;
+    send_signed_tx(&api_addr, &raw_ungrouped).await?;
+
+    let ungrouped_hash = json_hex_hash(&raw_ungrouped)
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing ungrouped tx hash"))?
+        .to_string();
+
+    let txs = list_transactions(&api_addr, "ungrouped=true&chainId=42431").await?;
+    assert_eq!(txs.len(), 1);
+    let tx = txs
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("missing ungrouped tx result"))?;
+    let tx_hash = tx.get("txHash").and_then(Value::as_str).unwrap_or_default();
+    assert_eq!(tx_hash, ungrouped_hash);
+    assert!(tx.get("groupId").is_none());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_list_transactions_paginates_with_cursor() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let mut raws = Vec::new();
+    for nonce in 0..3 {
+        let raw = build_signed_tx_with_nonce(&signer, nonce, 1_000_000_000, None)?;
+        send_signed_tx(&api_addr, &raw).await?;
+        raws.push(raw);
+    }
+
+    let query = format!(
+        "chainId={CHAIN_ID}&sender={}&sort=nonce&order=asc&limit=2",
+        signer.address()
+    );
+    let (first, cursor) = list_transactions_page(&api_addr, &query).await?;
+    let nonces: Vec<u64> = first
+        .iter()
+        .filter_map(|tx| tx.get("nonce").and_then(Value::as_u64))
+        .collect();
+    assert_eq!(nonces, vec![0, 1]);
+    let cursor = cursor.ok_or_else(|| anyhow::anyhow!("missing nextCursor"))?;
+
+    let (second, cursor) =
+        list_transactions_page(&api_addr, &format!("{query}&cursor={cursor}")).await?;
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].get("txHash"), Some(&json_hex_hash(&raws[2])));
+    assert!(cursor.is_none());
+
+    let resp = reqwest::Client::new()
+        .get(format!(
+            "http://{api_addr}/v1/transactions?chainId={CHAIN_ID}&sort=nonce&cursor=zz"
+        ))
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let future = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+    let query = format!(
+        "chainId={CHAIN_ID}&sender={}&createdAfter={future}",
+        signer.address()
+    );
+    assert!(list_transactions(&api_addr, &query).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_list_transactions_by_call_target() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let token = Address::repeat_byte(0x42);
+    let other = Address::repeat_byte(0x43);
+
+    let raw_batch = build_signed_tx_with_calls(&signer, 0, 1_000_000_000, None, &[other, token])?;
+    let raw_other = build_signed_tx_with_calls(&signer, 1, 1_000_000_000, None, &[other])?;
+    send_signed_tx(&api_addr, &raw_batch).await?;
+    send_signed_tx(&api_addr, &raw_other).await?;
+
+    let txs = list_transactions(&api_addr, &format!("chainId={CHAIN_ID}&to={token}")).await?;
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].get("txHash"), Some(&json_hex_hash(&raw_batch)));
+
+    let query = format!("chainId={CHAIN_ID}&sender={}&to={other}", signer.address());
+    assert_eq!(list_transactions(&api_addr, &query).await?.len(), 2);
+
+    let query = format!(
+        "chainId={CHAIN_ID}&sender={}&feePayer={}",
+        signer.address(),
+        Address::repeat_byte(0x44)
+    );
+    assert!(list_transactions(&api_addr, &query).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_list_transactions_reverted_filter() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_state().await?;
+    let signer = PrivateKeySigner::random();
+    let raw_ok = build_signed_tx_with_nonce(&signer, 0, 1_000_000_000, None)?;
+    let raw_reverted = build_signed_tx_with_nonce(&signer, 1, 1_000_000_000, None)?;
+
+    send_signed_tx(&api_addr, &raw_ok).await?;
+    send_signed_tx(&api_addr, &raw_reverted).await?;
+    wait_for_raw(&rpc_state, &raw_ok).await?;
+
+    for (raw, success) in [(&raw_ok, true), (&raw_reverted, false)] {
+        let tx_hash = json_hex_hash(raw)
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+            .to_string();
+        let record = db::get_tx_by_hash(
+            &state.db,
+            Some(CHAIN_ID),
+            &hex::decode(tx_hash.trim_start_matches("0x"))?,
+        )
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("missing tx record"))?;
+        let outcome = ReceiptOutcome {
+            receipt: serde_json::json!({ "transactionHash": tx_hash }),
+            block: Some(ReceiptBlock {
+                number: 100,
+                hash: vec![0xcd; 32],
+            }),
+            success,
+            gas_used: 21_000,
+            effective_gas_price: 1_000_000_000,
+            fee_token: Some(vec![0x20; 20]),
+        };
+        db::mark_executed(&state.db, record.id, &outcome).await?;
+    }
+
+    let query = format!(
+        "chainId={CHAIN_ID}&sender={}&reverted=true",
+        signer.address()
+    );
+    let txs = list_transactions(&api_addr, &query).await?;
+    assert_eq!(txs.len(), 1);
+    let tx = &txs[0];
+    assert_eq!(tx.get("txHash"), Some(&json_hex_hash(&raw_reverted)));
+    assert_eq!(tx.get("status").and_then(Value::as_str), Some("executed"));
+    assert_eq!(tx.get("reverted").and_then(Value::as_bool), Some(true));
+    assert_eq!(tx.get("gasUsed").and_then(Value::as_u64), Some(21_000));
+    assert_eq!(tx.get("blockNumber").and_then(Value::as_u64), Some(100));
+
+    let query = format!(
+        "chainId={CHAIN_ID}&sender={}&reverted=false",
+        signer.address()
+    );
+    let txs = list_transactions(&api_addr, &query).await?;
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].get("txHash"), Some(&json_hex_hash(&raw_ok)));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_retention_strips_and_archives_terminal_txs() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_state().await?;
+    let signer = PrivateKeySigner::random();
+    let raw_old = build_signed_tx_with_nonce(&signer, 0, 1_000_000_000, None)?;
+    let raw_recent = build_signed_tx_with_nonce(&signer, 1, 1_000_000_000, None)?;
+
+    send_signed_tx(&api_addr, &raw_old).await?;
+    send_signed_tx(&api_addr, &raw_recent).await?;
+    wait_for_raw(&rpc_state, &raw_old).await?;
+
+    for (raw, age_days) in [(&raw_old, 40), (&raw_recent, 5)] {
+        let tx_hash = hex::decode(
+            json_hex_hash(raw)
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+                .trim_start_matches("0x"),
+        )?;
+        sqlx::query(
+            "UPDATE txs SET status = 'confirmed', next_action_at = NULL, lease_owner = NULL, \
+             lease_until = NULL, updated_at = NOW() - make_interval(days => $1) WHERE tx_hash = $2",
+        )
+        .bind(age_days)
+        .bind(tx_hash)
+        .execute(&state.db)
+        .await?;
+    }
+
+    let config = RetentionConfig {
+        enabled: true,
+        policies: [(
+            "confirmed".to_string(),
+            RetentionPolicy {
+                strip_raw_tx_after_days: Some(1),
+                archive_after_days: Some(30),
+            },
+        )]
+        .into(),
+        ..RetentionConfig::default()
+    };
+    let run = retention::run_once(&state, &config).await?;
+    assert_eq!(run.archived, 1);
+    assert_eq!(run.stripped, 2);
+
+    let old_hash = json_hex_hash(&raw_old);
+    let resp = reqwest::get(format!(
+        "http://{api_addr}/v1/transactions/{}?chainId={CHAIN_ID}",
+        old_hash.as_str().unwrap_or_default()
+    ))
+    .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let (status, targets, attempts): (String, Value, Value) = sqlx::query_as(
+        "SELECT status, record->'call_targets', record->'broadcast_attempts' FROM txs_archive \
+         WHERE tx_hash = $1",
+    )
+    .bind(hex::decode(
+        old_hash
+            .as_str()
+            .unwrap_or_default()
+            .trim_start_matches("0x"),
+    )?)
+    .fetch_one(&state.db)
+    .await?;
+    assert_eq!(status, "confirmed");
+    assert_eq!(targets.as_array().map(Vec::len), Some(1));
+    assert!(
+        attempts
+            .pointer("/0/endpoint")
+            .and_then(Value::as_str)
+            .is_some_and(|endpoint| endpoint.starts_with("http://"))
+    );
+
+    let recent_hash = json_hex_hash(&raw_recent);
+    let recent = db::get_tx_by_hash(
+        &state.db,
+        Some(CHAIN_ID),
+        &hex::decode(
+            recent_hash
+                .as_str()
+                .unwrap_or_default()
+                .trim_start_matches("0x"),
+        )?,
+    )
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("recent tx should be kept"))?;
+    assert!(recent.raw_tx.is_none());
+
+    let health: Value = reqwest::get(format!("http://{api_addr}/health"))
+        .await?
+        .json()
+        .await?;
+    assert!(health["retention"]["archived"].as_u64() >= Some(1));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_retention_keeps_replaced_tx_while_nonce_is_pending() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state, state) = setup_e2e_with_state().await?;
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+
+    let original = build_signed_tx_with_fee(&signer, 100, Some(valid_after))?;
+    let replacement = build_signed_tx_with_fee(&signer, 110, Some(valid_after))?;
+    send_signed_tx(&api_addr, &original).await?;
+    let resp = submit_replacement(&api_addr, &replacement).await?;
+    assert!(resp.status().is_success());
+
+    let original_hash = hex::decode(
+        json_hex_hash(&original)
+            .as_str()
+            .unwrap_or_default()
+            .trim_start_matches("0x"),
+    )?;
+    sqlx::query("UPDATE txs SET updated_at = NOW() - make_interval(days => 40) WHERE tx_hash = $1")
+        .bind(&original_hash)
+        .execute(&state.db)
+        .await?;
+
+    let config = RetentionConfig {
+        enabled: true,
+        policies: [(
+            "replaced".to_string(),
+            RetentionPolicy {
+                strip_raw_tx_after_days: Some(1),
+                archive_after_days: Some(30),
+            },
+        )]
+        .into(),
+        ..RetentionConfig::default()
+    };
+    let run = retention::run_once(&state, &config).await?;
+    assert_eq!((run.stripped, run.archived), (0, 0));
+    let kept = db::get_tx_by_hash(&state.db, Some(CHAIN_ID), &original_hash)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("replaced tx should be kept"))?;
+    assert!(kept.raw_tx.is_some());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_requeue_rebroadcasts_invalid_tx() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_state().await?;
+    let raw_tx = build_signed_tx()?;
+    let tx_hash = json_hex_hash(&raw_tx)
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+        .to_string();
+
+    send_signed_tx(&api_addr, &raw_tx).await?;
+    wait_for_raw(&rpc_state, &raw_tx).await?;
+
+    let record = db::get_tx_by_hash(
+        &state.db,
+        Some(CHAIN_ID),
+        &hex::decode(tx_hash.trim_start_matches("0x"))?,
+    )
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("missing tx record"))?;
+    db::mark_invalid(&state.db, record.id, "rejected by operator").await?;
+    rpc_state.seen_raw.lock().await.clear();
+
+    let requeued = db::requeue_tx(&state.db, record.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("invalid tx should be requeued"))?;
+    assert_eq!(requeued.status, "retry_scheduled");
+    assert!(requeued.last_error.is_none());
+
+    // Redis lost the entry; rebuilding the schedule from the database brings it back.
+    assert_eq!(scheduler::rebuild_queues(&state, CHAIN_ID).await?, 1);
+    wait_for_raw(&rpc_state, &raw_tx).await?;
+
+    sqlx::query("UPDATE txs SET status = 'confirmed' WHERE id = $1")
+        .bind(record.id)
+        .execute(&state.db)
+        .await?;
+    assert!(db::requeue_tx(&state.db, record.id).await?.is_none());
+
+    sqlx::query("UPDATE txs SET status = 'canceled_locally', raw_tx = NULL WHERE id = $1")
+        .bind(record.id)
+        .execute(&state.db)
+        .await?;
+    assert!(db::requeue_tx(&state.db, record.id).await?.is_none());
+
+    let later = build_signed_tx_with_valid_after(Some(
+        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600,
+    ))?;
+    send_signed_tx(&api_addr, &later).await?;
+    let later = db::get_tx_by_hash(
+        &state.db,
+        Some(CHAIN_ID),
+        &hex::decode(
+            json_hex_hash(&later)
+                .as_str()
+                .unwrap_or_default()
+                .trim_start_matches("0x"),
+        )?,
+    )
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("missing tx record"))?;
+    db::mark_invalid(&state.db, later.id, "rejected by operator").await?;
+    let requeued = db::requeue_tx(&state.db, later.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("invalid tx should be requeued"))?;
+    assert_eq!(requeued.next_action_at, Some(later.eligible_at));
+
+    sqlx::query("UPDATE txs SET status = 'invalid', expires_at = NOW() WHERE id = $1")
+        .bind(later.id)
+        .execute(&state.db)
+        .await?;
+    assert!(db::requeue_tx(&state.db, later.id).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_reconcile_repairs_redis_schedule() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state, state) = setup_e2e_with_state().await?;
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+    let raw_tx = build_signed_tx_with_nonce(&signer, 0, 1_000_000_000, Some(valid_after))?;
+    let tx_hash = json_hex_hash(&raw_tx)
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+        .to_string();
+    send_signed_tx(&api_addr, &raw_tx).await?;
+
+    let ready_key = format!("watchtower:ready:{CHAIN_ID}");
+    let retry_key = format!("watchtower:retry:{CHAIN_ID}");
+    let mut redis = state.redis.clone().expect("redis is configured");
+    redis::cmd("FLUSHDB").query_async::<()>(&mut redis).await?;
+    redis::cmd("ZADD")
+        .arg(&retry_key)
+        .arg(1)
+        .arg(format!("0x{}", "ee".repeat(32)))
+        .query_async::<()>(&mut redis)
+        .await?;
+
+    let drift = scheduler::reconcile_queues(&state, CHAIN_ID).await?;
+    assert_eq!(
+        drift,
+        QueueDrift {
+            missing: 1,
+            orphaned: 1
+        }
+    );
+    let score: Option<u64> = redis::cmd("ZSCORE")
+        .arg(&ready_key)
+        .arg(&tx_hash)
+        .query_async(&mut redis)
+        .await?;
+    assert_eq!(score, Some(valid_after));
+    let retry_len: u64 = redis::cmd("ZCARD")
+        .arg(&retry_key)
+        .query_async(&mut redis)
+        .await?;
+    assert_eq!(retry_len, 0);
+
+    assert_eq!(
+        scheduler::reconcile_queues(&state, CHAIN_ID).await?,
+        QueueDrift::default()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_reconcile_keeps_accepted_broadcasts() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state, state) = setup_e2e_with_state().await?;
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+    let raw_tx = build_signed_tx_with_nonce(&signer, 0, 1_000_000_000, Some(valid_after))?;
+    let tx_hash = json_hex_hash(&raw_tx)
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+        .to_string();
+    send_signed_tx(&api_addr, &raw_tx).await?;
+
+    // The state an accepted broadcast leaves behind while it waits to be rebroadcast.
+    let rebroadcast_at: i64 = sqlx::query_scalar(
+        r#"
+        UPDATE txs
+        SET status = 'broadcasting', attempts = 1, next_action_at = NOW() + INTERVAL '1 hour'
+        WHERE tx_hash = decode($1, 'hex')
+        RETURNING FLOOR(EXTRACT(EPOCH FROM next_action_at))::BIGINT
+        "#,
+    )
+    .bind(tx_hash.trim_start_matches("0x"))
+    .fetch_one(&state.db)
+    .await?;
+
+    assert_eq!(scheduler::rebuild_queues(&state, CHAIN_ID).await?, 1);
+    assert_eq!(
+        scheduler::reconcile_queues(&state, CHAIN_ID).await?,
+        QueueDrift::default()
+    );
+    let mut redis = state.redis.clone().expect("redis is configured");
+    let score: Option<i64> = redis::cmd("ZSCORE")
+        .arg(format!("watchtower:retry:{CHAIN_ID}"))
+        .arg(&tx_hash)
+        .query_async(&mut redis)
+        .await?;
+    assert_eq!(score, Some(rebroadcast_at));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_postgres_only_mode_broadcasts_on_notify() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_options(false).await?;
+    assert!(state.redis.is_none());
+
+    let health: Value = reqwest::get(format!("http://{api_addr}/health"))
+        .await?
+        .json()
+        .await?;
+    assert_eq!(health["status"], "ok");
+    assert_eq!(health["scheduler"]["mode"], "postgres");
+    assert!(health["dependencies"].get("redis").is_none());
+
+    let raw_tx = build_signed_tx()?;
+    send_signed_tx(&api_addr, &raw_tx).await?;
+    wait_for_raw(&rpc_state, &raw_tx).await?;
+
+    // An accepted tx is rebroadcast on its backoff rather than after the listener's max wait.
+    timeout(Duration::from_secs(5), async {
+        loop {
+            let seen = rpc_state.seen_raw.lock().await;
+            if seen.iter().filter(|raw| **raw == raw_tx).count() >= 2 {
+                return;
+            }
+            drop(seen);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("accepted tx was not rebroadcast"))?;
+
+    // A future tx is picked up when it falls due rather than on the next notification.
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let delayed = build_signed_tx_with_valid_after(Some(now + 2))?;
+    send_signed_tx(&api_addr, &delayed).await?;
+    assert_not_broadcast_within(&rpc_state, &delayed, Duration::from_secs(1)).await?;
+    wait_for_raw_with_deadline(&rpc_state, &delayed, Duration::from_secs(6)).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_shutdown_releases_held_leases() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_state().await?;
+    let raw_tx = build_signed_tx()?;
+    let tx_hash = json_hex_hash(&raw_tx)
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?
+        .to_string();
+
+    send_signed_tx(&api_addr, &raw_tx).await?;
+    wait_for_raw(&rpc_state, &raw_tx).await?;
+
+    // Stop the scheduler, then simulate a broadcast that was still leased at exit.
+    state.shutdown.trigger();
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    sqlx::query(
+        r#"
+        UPDATE txs
+        SET status = 'broadcasting',
+            lease_owner = $1,
+            lease_until = NOW() + INTERVAL '1 minute',
+            next_action_at = NULL
+        WHERE tx_hash = decode($2, 'hex')
+        "#,
+    )
+    .bind(state.shutdown.scheduler_lease_owner(CHAIN_ID))
+    .bind(tx_hash.trim_start_matches("0x"))
+    .execute(&state.db)
+    .await?;
+
+    shutdown::drain(&state).await;
+
+    let (status, lease_owner): (String, Option<String>) =
+        sqlx::query_as("SELECT status, lease_owner FROM txs WHERE tx_hash = decode($1, 'hex')")
+            .bind(tx_hash.trim_start_matches("0x"))
+            .fetch_one(&state.db)
+            .await?;
+    assert_eq!(status, "retry_scheduled");
+    assert!(lease_owner.is_none());
+
+    let mut redis = state.redis.clone().expect("redis is configured");
+    let score: Option<f64> = redis::cmd("ZSCORE")
+        .arg(format!("watchtower:retry:{CHAIN_ID}"))
+        .arg(&tx_hash)
+        .query_async(&mut redis)
+        .await?;
+    assert!(score.is_some());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_api_keys_enforce_rate_limit_and_chain_scope() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state, state) = setup_e2e_with_state().await?;
+    let client = reqwest::Client::new();
+
+    let limited_key = api_keys::generate_key();
+    db::insert_api_key(
+        &state.db,
+        &NewApiKey {
+            name: "limited".to_string(),
+            key_hash: api_keys::hash_key(&limited_key),
+            requests_per_minute: 2,
+            txs_per_day: 100,
+            max_queued_per_sender: 10,
+            chain_ids: None,
+            senders: None,
+        },
+    )
+    .await?;
+
+    let list_url = format!("http://{api_addr}/v1/transactions?chainId={CHAIN_ID}");
+    for _ in 0..2 {
+        let resp = client
+            .get(&list_url)
+            .header(api_keys::API_KEY_HEADER, &limited_key)
+            .send()
+            .await?;
+        assert!(resp.status().is_success());
+    }
+    let resp = client
+        .get(&list_url)
+        .header(api_keys::API_KEY_HEADER, &limited_key)
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    let retry_after = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    assert!(retry_after.is_some_and(|secs| secs >= 1));
+
+    let resp = client
+        .get(&list_url)
+        .header(api_keys::API_KEY_HEADER, "wtk_unknown")
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let scoped_key = api_keys::generate_key();
+    db::insert_api_key(
+        &state.db,
+        &NewApiKey {
+            name: "other-chain".to_string(),
+            key_hash: api_keys::hash_key(&scoped_key),
+            requests_per_minute: 60,
+            txs_per_day: 100,
+            max_queued_per_sender: 10,
+            chain_ids: Some(vec![1]),
+            senders: None,
+        },
+    )
+    .await?;
+    let resp = client
+        .post(format!("http://{api_addr}/v1/transactions"))
+        .header(api_keys::API_KEY_HEADER, &scoped_key)
+        .json(&serde_json::json!({
+            "chainId": CHAIN_ID,
+            "transactions": [build_signed_tx()?],
+        }))
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_daily_quota_counts_only_stored_nonces() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state, state) = setup_e2e_with_state().await?;
+    let client = reqwest::Client::new();
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+
+    let key = api_keys::generate_key();
+    db::insert_api_key(
+        &state.db,
+        &NewApiKey {
+            name: "quota".to_string(),
+            key_hash: api_keys::hash_key(&key),
+            requests_per_minute: 60,
+            txs_per_day: 2,
+            max_queued_per_sender: 10,
+            chain_ids: None,
+            senders: None,
+        },
+    )
+    .await?;
+    let submit = |raw_tx: String, replace: bool| {
+        client
+            .post(format!("http://{api_addr}/v1/transactions"))
+            .header(api_keys::API_KEY_HEADER, &key)
+            .json(&serde_json::json!({
+                "chainId": CHAIN_ID,
+                "transactions": [raw_tx],
+                "replace": replace,
+            }))
+            .send()
+    };
+
+    let original = build_signed_tx_with_fee(&signer, 100, Some(valid_after))?;
+    assert!(submit(original.clone(), false).await?.status().is_success());
+    // Resubmitting a known transaction, a rejected replacement and an accepted replacement
+    // leave the quota as it was.
+    assert!(submit(original, false).await?.status().is_success());
+    let underpriced = build_signed_tx_with_fee(&signer, 105, Some(valid_after))?;
+    let resp = submit(underpriced, true).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let replacement = build_signed_tx_with_fee(&signer, 110, Some(valid_after))?;
+    assert!(submit(replacement, true).await?.status().is_success());
+
+    let next = build_signed_tx_with_nonce(&signer, 1, 100, Some(valid_after))?;
+    assert!(submit(next, false).await?.status().is_success());
+    let over = build_signed_tx_with_nonce(&signer, 2, 100, Some(valid_after))?;
+    let resp = submit(over, false).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_fee_bump_replaces_pending_tx() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+
+    let original = build_signed_tx_with_fee(&signer, 100, Some(valid_after))?;
+    let underpriced = build_signed_tx_with_fee(&signer, 105, Some(valid_after))?;
+    let replacement = build_signed_tx_with_fee(&signer, 110, Some(valid_after))?;
+
+    send_signed_tx(&api_addr, &original).await?;
+
+    let resp = submit_replacement(&api_addr, &underpriced).await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let resp = submit_replacement(&api_addr, &replacement).await?;
+    assert!(resp.status().is_success());
+
+    let original_hash = json_hex_hash(&original);
+    let replacement_hash = json_hex_hash(&replacement);
+    let original_info =
+        get_transaction(&api_addr, original_hash.as_str().unwrap_or_default()).await?;
+    assert_eq!(
+        original_info.get("status").and_then(Value::as_str),
+        Some("replaced")
+    );
+    assert_eq!(original_info.get("replacedBy"), Some(&replacement_hash));
+
+    let sender = format!("{:#x}", signer.address());
+    let active = list_transactions(
+        &api_addr,
+        &format!("sender={sender}&status=queued&chainId={CHAIN_ID}"),
+    )
+    .await?;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].get("txHash"), Some(&replacement_hash));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_mined_replaced_tx_is_executed() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_state().await?;
+    watcher::start(state);
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+
+    let original = build_signed_tx_with_fee(&signer, 100, None)?;
+    let original_hash = json_hex_hash(&original);
+    let original_hash = original_hash.as_str().unwrap_or_default();
+    send_signed_tx(&api_addr, &original).await?;
+    wait_for_raw(&rpc_state, &original).await?;
+    timeout(Duration::from_secs(5), async {
+        loop {
+            let tx = get_transaction(&api_addr, original_hash).await?;
+            if tx.get("attempts").and_then(Value::as_i64) > Some(0) {
+                return anyhow::Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out waiting for the first broadcast"))??;
+
+    let replacement = build_signed_tx_with_fee(&signer, 110, Some(valid_after))?;
+    let replacement_hash = json_hex_hash(&replacement);
+    let resp = submit_replacement(&api_addr, &replacement).await?;
+    assert!(resp.status().is_success());
+
+    rpc_state.mined.lock().await.push(original_hash.to_string());
+    rpc_state.current_nonce.store(1, Ordering::SeqCst);
+
+    timeout(Duration::from_secs(10), async {
+        loop {
+            let tx = get_transaction(&api_addr, original_hash).await?;
+            if tx.get("status").and_then(Value::as_str) == Some("executed") {
+                return anyhow::Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("replaced tx was not recorded as executed"))??;
+
+    let replacement_info =
+        get_transaction(&api_addr, replacement_hash.as_str().unwrap_or_default()).await?;
+    assert_eq!(
+        replacement_info.get("status").and_then(Value::as_str),
+        Some("replaced")
+    );
+    assert_eq!(
+        replacement_info.get("replacedBy").and_then(Value::as_str),
+        Some(original_hash)
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_resubmitting_replaced_tx_is_already_known() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+
+    let original = build_signed_tx_with_fee(&signer, 100, Some(valid_after))?;
+    let replacement = build_signed_tx_with_fee(&signer, 110, Some(valid_after))?;
+    send_signed_tx(&api_addr, &original).await?;
+    let resp = submit_replacement(&api_addr, &replacement).await?;
+    assert!(resp.status().is_success());
+
+    let resp = submit_replacement(&api_addr, &original).await?;
+    assert!(resp.status().is_success());
+    let body: Value = resp.json().await?;
+    let result = &body["results"][0];
+    assert_eq!(result.get("alreadyKnown"), Some(&Value::Bool(true)));
+    assert_eq!(
+        result.get("status").and_then(Value::as_str),
+        Some("replaced")
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_fee_ladder_escalates_to_next_rung() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+
+    let rungs = [
+        build_signed_tx_with_fee(&signer, 100, None)?,
+        build_signed_tx_with_fee(&signer, 110, None)?,
+        build_signed_tx_with_fee(&signer, 121, None)?,
+    ];
+    let body = serde_json::json!({
+        "chainId": CHAIN_ID,
+        "escalation": [{
+            "rungs": rungs,
+            "escalateAfterAttempts": 2,
+            "escalateAfterSeconds": 3600
+        }]
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("http://{api_addr}/v1/transactions"))
+        .json(&body)
+        .send()
+        .await?;
+    assert!(resp.status().is_success());
+    let submitted: Value = resp.json().await?;
+    let ladder = &submitted["ladders"][0];
+    let statuses: Vec<_> = ladder["results"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|result| result["status"].as_str().unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(statuses, ["queued", "standby", "standby"]);
+
+    wait_for_raw(&rpc_state, &rungs[0]).await?;
+    wait_for_raw(&rpc_state, &rungs[1]).await?;
+    wait_for_raw(&rpc_state, &rungs[2]).await?;
+
+    let first_hash = json_hex_hash(&rungs[0]);
+    let second_hash = json_hex_hash(&rungs[1]);
+    let first = get_transaction(&api_addr, first_hash.as_str().unwrap_or_default()).await?;
+    assert_eq!(
+        first.get("status").and_then(Value::as_str),
+        Some("replaced")
+    );
+    assert_eq!(first.get("replacedBy"), Some(&second_hash));
+    assert_eq!(first.get("ladderId"), ladder.get("ladderId"));
+
+    let resubmitted: Value = client
+        .post(format!("http://{api_addr}/v1/transactions"))
+        .json(&body)
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(resubmitted["ladders"][0]["ladderId"], ladder["ladderId"]);
+    assert_eq!(
+        resubmitted["ladders"][0]["results"][0]["alreadyKnown"],
+        Value::Bool(true)
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_rejected_ladder_stores_nothing_from_the_batch() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, _rpc_state) = setup_e2e().await?;
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
+
+    let outside_ladder = build_signed_tx_with_nonce(&signer, 2, 110, Some(valid_after))?;
+    send_signed_tx(&api_addr, &outside_ladder).await?;
+
+    let plain = build_signed_tx_with_nonce(&signer, 1, 100, Some(valid_after))?;
+    let rungs = [
+        build_signed_tx_with_nonce(&signer, 2, 100, Some(valid_after))?,
+        outside_ladder,
+    ];
+    let resp = reqwest::Client::new()
+        .post(format!("http://{api_addr}/v1/transactions"))
+        .json(&serde_json::json!({
+            "chainId": CHAIN_ID,
+            "transactions": [plain],
+            "escalation": [{ "rungs": rungs }]
+        }))
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    for raw_tx in [&plain, &rungs[0]] {
+        let tx_hash = json_hex_hash(raw_tx);
+        let resp = reqwest::get(format!(
+            "http://{api_addr}/v1/transactions/{}?chainId={CHAIN_ID}",
+            tx_hash.as_str().unwrap_or_default()
+        ))
+        .await?;
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
     }
-    assert!(find_group(&groups_active, &group_one_hex).is_some());
-    assert!(find_group(&groups_active, &group_two_hex).is_none());
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn e2e_list_transactions_ungrouped_filter() -> anyhow::Result<()> {
+async fn e2e_fee_ladder_rungs_respect_group_valid_before_order() -> anyhow::Result<()> {
     let _guard = acquire_e2e_lock().await;
     let (api_addr, _rpc_state) = setup_e2e().await?;
     let signer = PrivateKeySigner::random();
-    let nonce_key = build_group_nonce_key(1, 11);
+    let nonce_key = build_group_nonce_key(1, 14);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
-    let raw_grouped = build_group_signed_tx_with_valid_after(&signer, nonce_key, None)?;
-    let raw_ungrouped = build_signed_tx()?;
+    let later = build_group_signed_tx_with_window(
+        &signer,
+        nonce_key,
+        1,
+        100,
+        Some(now + 3600),
+        Some(now + 7200),
+    )?;
+    send_signed_tx(&api_addr, &later).await?;
+
+    let rungs = [
+        build_group_signed_tx_with_window(
+            &signer,
+            nonce_key,
+            0,
+            100,
+            Some(now + 3600),
+            Some(now + 10800),
+        )?,
+        build_group_signed_tx_with_window(
+            &signer,
+            nonce_key,
+            0,
+            110,
+            Some(now + 3600),
+            Some(now + 10800),
+        )?,
+    ];
+    let resp = reqwest::Client::new()
+        .post(format!("http://{api_addr}/v1/transactions"))
+        .json(&serde_json::json!({
+            "chainId": CHAIN_ID,
+            "escalation": [{ "rungs": rungs }]
+        }))
+        .send()
+        .await?;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: Value = resp.json().await?;
+    assert!(
+        body.get("error")
+            .and_then(Value::as_str)
+            .is_some_and(|error| error.contains("valid_before order"))
+    );
 
-    send_signed_tx(&api_addr, &raw_grouped).await?;
-    send_signed_tx(&api_addr, &raw_ungrouped).await?;
+    Ok(())
+}
 
-    let ungrouped_hash = json_hex_hash(&raw_ungrouped)
-        .as_str()
-        .ok_or_else(|| anyhow::anyhow!("missing ungrouped tx hash"))?
-        .to_string();
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn e2e_successor_nonce_waits_for_predecessor() -> anyhow::Result<()> {
+    let _guard = acquire_e2e_lock().await;
+    let (api_addr, rpc_state, state) = setup_e2e_with_state().await?;
+    watcher::start(state);
+    let signer = PrivateKeySigner::random();
+    let valid_after = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;
 
-    let txs = list_transactions(&api_addr, "ungrouped=true&chainId=42431").await?;
-    assert_eq!(txs.len(), 1);
-    let tx = txs
-        .first()
-        .ok_or_else(|| anyhow::anyhow!("missing ungrouped tx result"))?;
-    let tx_hash = tx.get("txHash").and_then(Value::as_str).unwrap_or_default();
-    assert_eq!(tx_hash, ungrouped_hash);
-    assert!(tx.get("groupId").is_none());
+    let first = build_signed_tx_with_nonce(&signer, 0, 1, Some(valid_after))?;
+    let second = build_signed_tx_with_nonce(&signer, 1, 1, None)?;
+    send_signed_tx(&api_addr, &first).await?;
+    send_signed_tx(&api_addr, &second).await?;
+
+    assert_not_broadcast_within(&rpc_state, &second, Duration::from_millis(1500)).await?;
+
+    // Nonce 0 is consumed elsewhere, so the watcher retires it and nonce 1 goes out.
+    rpc_state.current_nonce.store(1, Ordering::SeqCst);
+    wait_for_raw(&rpc_state, &second).await?;
+
+    let first_hash = json_hex_hash(&first);
+    let first_info = get_transaction(&api_addr, first_hash.as_str().unwrap_or_default()).await?;
+    assert_eq!(
+        first_info.get("status").and_then(Value::as_str),
+        Some("stale_by_nonce")
+    );
 
     Ok(())
 }
 
+async fn get_transaction(api_addr: &SocketAddr, tx_hash: &str) -> anyhow::Result<Value> {
+    let resp = reqwest::get(format!(
+        "http://{api_addr}/v1/transactions/{tx_hash}?chainId={CHAIN_ID}"
+    ))
+    .await?;
+    assert!(resp.status().is_success());
+    Ok(resp.json().await?)
+}
+
 async fn send_signed_tx(api_addr: &SocketAddr, raw_tx: &str) -> anyhow::Result<()> {
     let client = reqwest::Client::new();
     let resp = client
@@ -249,22 +1790,28 @@ async fn send_signed_tx(api_addr: &SocketAddr, raw_tx: &str) -> anyhow::Result<(
     Ok(())
 }
 
+async fn submit_replacement(
+    api_addr: &SocketAddr,
+    raw_tx: &str,
+) -> anyhow::Result<reqwest::Response> {
+    Ok(reqwest::Client::new()
+        .post(format!("http://{api_addr}/v1/transactions"))
+        .json(&serde_json::json!({
+            "chainId": CHAIN_ID,
+            "transactions": [raw_tx],
+            "replace": true
+        }))
+        .send()
+        .await?)
+}
+
 async fn cancel_group(
     api_addr: &SocketAddr,
     sender: Address,
     group_id: [u8; 16],
-    auth_header: &str,
+    auth: HeaderMap,
 ) -> anyhow::Result<()> {
-    let client = reqwest::Client::new();
-    let sender_hex = format!("0x{}", hex::encode(sender.as_slice()));
-    let group_hex = format!("0x{}", hex::encode(group_id));
-    let resp = client
-        .post(format!(
-            "http://{api_addr}/v1/senders/{sender_hex}/groups/{group_hex}/cancel"
-        ))
-        .header("Authorization", auth_header)
-        .send()
-        .await?;
+    let resp = post_cancel_group(api_addr, sender, group_id, auth).await?;
 
     assert!(resp.status().is_success());
     let body: Value = resp.json().await?;
@@ -277,6 +1824,19 @@ async fn cancel_group(
     Ok(())
 }
 
+async fn post_webhook(
+    api_addr: &SocketAddr,
+    body: Value,
+    auth: HeaderMap,
+) -> anyhow::Result<reqwest::Response> {
+    Ok(reqwest::Client::new()
+        .post(format!("http://{api_addr}/v1/webhooks"))
+        .headers(auth)
+        .json(&body)
+        .send()
+        .await?)
+}
+
 async fn cancel_transaction(
     api_addr: &SocketAddr,
     tx_hash: &str,
@@ -296,6 +1856,85 @@ async fn cancel_transaction(
     Ok(body)
 }
 
+async fn post_cancel_group(
+    api_addr: &SocketAddr,
+    sender: Address,
+    group_id: [u8; 16],
+    auth: HeaderMap,
+) -> anyhow::Result<reqwest::Response> {
+    post_group_action(api_addr, sender, group_id, "cancel", auth).await
+}
+
+async fn post_cancel_group_nonces(
+    api_addr: &SocketAddr,
+    sender: Address,
+    group_id: [u8; 16],
+    from_nonce: u64,
+    auth: HeaderMap,
+) -> anyhow::Result<reqwest::Response> {
+    let client = reqwest::Client::new();
+    let sender_hex = format!("0x{}", hex::encode(sender.as_slice()));
+    let group_hex = format!("0x{}", hex::encode(group_id));
+    Ok(client
+        .post(format!(
+            "http://{api_addr}/v1/senders/{sender_hex}/groups/{group_hex}/cancel?chainId={CHAIN_ID}&fromNonce={from_nonce}"
+        ))
+        .headers(auth)
+        .send()
+        .await?)
+}
+
+async fn get_group(
+    api_addr: &SocketAddr,
+    sender: Address,
+    group_id: [u8; 16],
+    query: &str,
+) -> anyhow::Result<Value> {
+    let sender_hex = format!("0x{}", hex::encode(sender.as_slice()));
+    let group_hex = format!("0x{}", hex::encode(group_id));
+    let resp = reqwest::get(format!(
+        "http://{api_addr}/v1/senders/{sender_hex}/groups/{group_hex}?chainId={CHAIN_ID}&{query}"
+    ))
+    .await?;
+
+    assert!(resp.status().is_success());
+    Ok(resp.json().await?)
+}
+
+async fn post_group_action(
+    api_addr: &SocketAddr,
+    sender: Address,
+    group_id: [u8; 16],
+    action: &str,
+    auth: HeaderMap,
+) -> anyhow::Result<reqwest::Response> {
+    let client = reqwest::Client::new();
+    let sender_hex = format!("0x{}", hex::encode(sender.as_slice()));
+    let group_hex = format!("0x{}", hex::encode(group_id));
+    Ok(client
+        .post(format!(
+            "http://{api_addr}/v1/senders/{sender_hex}/groups/{group_hex}/{action}?chainId={CHAIN_ID}"
+        ))
+        .headers(auth)
+        .send()
+        .await?)
+}
+
+async fn delete_transaction(
+    api_addr: &SocketAddr,
+    tx_hash: &str,
+    auth: HeaderMap,
+) -> anyhow::Result<reqwest::Response> {
+    let client = reqwest::Client::new();
+    Ok(client
+        .delete(format!(
+            "http://{api_addr}/v1/transactions/{tx_hash}?chainId={CHAIN_ID}"
+        ))
+        .headers(auth)
+        .send()
+        .await?)
+}
+
 async fn list_groups(
     api_addr: &SocketAddr,
     sender_hex: &str,
@@ -309,8 +1948,8 @@ async fn list_groups(
     };
     let resp = client.get(url).send().await?;
     assert!(resp.status().is_success());
-    let body: Vec<Value> = resp.json().await?;
-    Ok(body)
+    let (groups, _) = page_items(resp.json().await?, "groups")?;
+    Ok(groups)
 }
 
 fn find_group<'a>(groups: &'a [Value], group_id: &str) -> Option<&'a Value> {
@@ -346,7 +1985,37 @@ async fn send_signed_tx_via_rpc(api_addr: &SocketAddr, raw_tx: &str) -> anyhow::
     Ok(result.to_string())
 }
 
+async fn list_broadcast_attempts(
+    api_addr: &SocketAddr,
+    raw_tx: &str,
+) -> anyhow::Result<Vec<Value>> {
+    let tx_hash = json_hex_hash(raw_tx);
+    let tx_hash = tx_hash
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing tx hash"))?;
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!(
+            "http://{api_addr}/v1/transactions/{tx_hash}/attempts?chainId={CHAIN_ID}"
+        ))
+        .send()
+        .await?;
+
+    assert!(resp.status().is_success());
+    let body: Vec<Value> = resp.json().await?;
+
+    Ok(body)
+}
+
 async fn list_transactions(api_addr: &SocketAddr, query: &str) -> anyhow::Result<Vec<Value>> {
+    let (transactions, _) = list_transactions_page(api_addr, query).await?;
+    Ok(transactions)
+}
+
+async fn list_transactions_page(
+    api_addr: &SocketAddr,
+    query: &str,
+) -> anyhow::Result<(Vec<Value>, Option<String>)> {
     let client = reqwest::Client::new();
     let url = if query.is_empty() {
         format!("http://{api_addr}/v1/transactions")
@@ -355,8 +2024,20 @@ async fn list_transactions(api_addr: &SocketAddr, query: &str) -> anyhow::Result
     };
     let resp = client.get(url).send().await?;
     assert!(resp.status().is_success());
-    let body: Vec<Value> = resp.json().await?;
-    Ok(body)
+    page_items(resp.json().await?, "transactions")
+}
+
+fn page_items(body: Value, field: &str) -> anyhow::Result<(Vec<Value>, Option<String>)> {
+    let items = body
+        .get(field)
+        .and_then(Value::as_array)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("missing {field} in list response"))?;
+    let next_cursor = body
+        .get("nextCursor")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Ok((items, next_cursor))
 }
 
 async fn start_fake_rpc() -> anyhow::Result<(SocketAddr, RpcState)> {
@@ -373,6 +2054,31 @@ async fn start_fake_rpc() -> anyhow::Result<(SocketAddr, RpcState)> {
     Ok((addr, state))
 }
 
+async fn start_webhook_receiver() -> anyhow::Result<(SocketAddr, WebhookReceiverState)> {
+    let state = WebhookReceiverState::default();
+    let app = Router::new()
+        .route("/", post(webhook_handler))
+        .with_state(state.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        axum::serve(listener, app)
+            .await
+            .expect("webhook receiver failed");
+    });
+
+    Ok((addr, state))
+}
+
+async fn webhook_handler(
+    axum::extract::State(state): axum::extract::State<WebhookReceiverState>,
+    headers: axum::http::HeaderMap,
+    body: String,
+) -> axum::http::StatusCode {
+    state.deliveries.lock().await.push((headers, body));
+    axum::http::StatusCode::NO_CONTENT
+}
+
 async fn rpc_handler(
     axum::extract::State(state): axum::extract::State<RpcState>,
     Json(payload): Json<Value>,
@@ -405,7 +2111,23 @@ async fn rpc_handler(
             "0x{:x}",
             state.current_nonce.load(Ordering::SeqCst)
         )),
-        "eth_getTransactionReceipt" => Value::Null,
+        // Only the nonce precompile is called, so every call returns the current nonce.
+        "eth_call" => Value::from(format!(
+            "0x{:064x}",
+            state.current_nonce.load(Ordering::SeqCst)
+        )),
+        "eth_getTransactionReceipt" => {
+            let hash = params
+                .first()
+                .and_then(|value| value.as_str())
+                .unwrap_or_default()
+                .to_lowercase();
+            if state.mined.lock().await.contains(&hash) {
+                fake_receipt(&hash)
+            } else {
+                Value::Null
+            }
+        }
         "web3_clientVersion" => Value::from("temprano-watchtower-test"),
         _ => Value::Null,
     };
@@ -417,6 +2139,25 @@ async fn rpc_handler(
     }))
 }
 
+fn fake_receipt(tx_hash: &str) -> Value {
+    serde_json::json!({
+        "type": "0x2",
+        "status": "0x1",
+        "transactionHash": tx_hash,
+        "transactionIndex": "0x0",
+        "blockHash": format!("0x{}", "11".repeat(32)),
+        "blockNumber": "0x10",
+        "from": format!("{:#x}", Address::ZERO),
+        "to": format!("{:#x}", Address::ZERO),
+        "cumulativeGasUsed": "0x5208",
+        "gasUsed": "0x5208",
+        "effectiveGasPrice": "0x64",
+        "contractAddress": null,
+        "logs": [],
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+    })
+}
+
 fn json_hex_hash(raw: &str) -> Value {
     let raw = raw.strip_prefix("0x").unwrap_or(raw);
     let bytes = hex::decode(raw).unwrap_or_default();
@@ -515,10 +2256,96 @@ fn build_signed_tx_with_valid_after(valid_after: Option<u64>) -> anyhow::Result<
     Ok(format!("0x{}", hex::encode(buf)))
 }
 
+fn build_signed_tx_with_fee(
+    signer: &PrivateKeySigner,
+    max_fee_per_gas: u128,
+    valid_after: Option<u64>,
+) -> anyhow::Result<String> {
+    build_signed_tx_with_nonce(signer, 0, max_fee_per_gas, valid_after)
+}
+
+fn build_signed_tx_with_nonce(
+    signer: &PrivateKeySigner,
+    nonce: u64,
+    max_fee_per_gas: u128,
+    valid_after: Option<u64>,
+) -> anyhow::Result<String> {
+    build_signed_tx_with_calls(
+        signer,
+        nonce,
+        max_fee_per_gas,
+        valid_after,
+        &[Address::ZERO],
+    )
+}
+
+fn build_signed_tx_with_calls(
+    signer: &PrivateKeySigner,
+    nonce: u64,
+    max_fee_per_gas: u128,
+    valid_after: Option<u64>,
+    targets: &[Address],
+) -> anyhow::Result<String> {
+    let calls = targets
+        .iter()
+        .map(|target| Call {
+            to: TxKind::Call(*target),
+            value: U256::ZERO,
+            input: Bytes::default(),
+        })
+        .collect();
+
+    let tx = TempoTransaction {
+        chain_id: CHAIN_ID,
+        fee_token: None,
+        max_priority_fee_per_gas: 1,
+        max_fee_per_gas,
+        gas_limit: 21000,
+        calls,
+        access_list: alloy::rpc::types::AccessList::default(),
+        nonce_key: U256::ZERO,
+        nonce,
+        fee_payer_signature: None,
+        valid_before: None,
+        valid_after,
+        key_authorization: None,
+        tempo_authorization_list: Vec::new(),
+    };
+
+    let signature = signer.sign_hash_sync(&tx.signature_hash())?;
+    let tempo_sig = TempoSignature::Primitive(PrimitiveSignature::Secp256k1(signature));
+    let signed: AASigned = tx.into_signed(tempo_sig);
+
+    let mut buf = Vec::new();
+    signed.eip2718_encode(&mut buf);
+
+    Ok(format!("0x{}", hex::encode(buf)))
+}
+
 fn build_group_signed_tx_with_valid_after(
     signer: &PrivateKeySigner,
     nonce_key: U256,
     valid_after: Option<u64>,
+) -> anyhow::Result<String> {
+    build_group_signed_tx(signer, nonce_key, 0, valid_after)
+}
+
+fn build_group_signed_tx(
+    signer: &PrivateKeySigner,
+    nonce_key: U256,
+    nonce: u64,
+    valid_after: Option<u64>,
+) -> anyhow::Result<String> {
+    build_group_signed_tx_with_window(signer, nonce_key, nonce, 1, valid_after, None)
+}
+
+fn build_group_signed_tx_with_window(
+    signer: &PrivateKeySigner,
+    nonce_key: U256,
+    nonce: u64,
+    max_fee_per_gas: u128,
+    valid_after: Option<u64>,
+    valid_before: Option<u64>,
 ) -> anyhow::Result<String> {
     let call = Call {
         to: TxKind::Call(Address::ZERO),
@@ -530,14 +2357,14 @@ fn build_group_signed_tx_with_valid_after(
         chain_id: CHAIN_ID,
         fee_token: None,
         max_priority_fee_per_gas: 1,
-        max_fee_per_gas: 1,
+        max_fee_per_gas,
         gas_limit: 21000,
         calls: vec![call],
         access_list: alloy::rpc::types::AccessList::default(),
         nonce_key,
-        nonce: 0,
+        nonce,
         fee_payer_signature: None,
-        valid_before: None,
+        valid_before,
         valid_after,
         key_authorization: None,
         tempo_authorization_list: Vec::new(),
@@ -553,10 +2380,51 @@ fn build_group_signed_tx_with_valid_after(
     Ok(format!("0x{}", hex::encode(buf)))
 }
 
-fn build_cancel_auth(signer: &PrivateKeySigner, group_id: [u8; 16]) -> anyhow::Result<String> {
-    let hash = keccak256(group_id);
+fn build_cancel_auth(signer: &PrivateKeySigner, group_id: [u8; 16]) -> anyhow::Result<HeaderMap> {
+    build_typed_auth(signer, SignedAction::CancelGroup(&group_id))
+}
+
+fn build_tx_cancel_auth(signer: &PrivateKeySigner, tx_hash: &str) -> anyhow::Result<HeaderMap> {
+    let tx_hash = hex::decode(tx_hash.trim_start_matches("0x"))?;
+    build_typed_auth(signer, SignedAction::CancelTransaction(&tx_hash))
+}
+
+fn build_typed_auth(
+    signer: &PrivateKeySigner,
+    target: SignedAction<'_>,
+) -> anyhow::Result<HeaderMap> {
+    let expiry = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 300;
+    let nonce = U256::from_be_slice(Uuid::new_v4().as_bytes());
+    let authorization = Authorization::Typed {
+        signature: Vec::new(),
+        expiry,
+        nonce,
+    };
+    let hash = auth::signing_hash(&authorization, CHAIN_ID, signer.address(), target)
+        .map_err(anyhow::Error::msg)?;
     let signature = signer.sign_hash_sync(&hash)?;
-    Ok(format!("Signature 0x{}", hex::encode(signature.as_bytes())))
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "authorization",
+        format!("EIP712 0x{}", hex::encode(signature.as_bytes())).parse()?,
+    );
+    headers.insert("x-auth-expiry", expiry.to_string().parse()?);
+    headers.insert("x-auth-nonce", nonce.to_string().parse()?);
+    Ok(headers)
+}
+
+fn build_legacy_cancel_auth(
+    signer: &PrivateKeySigner,
+    group_id: [u8; 16],
+) -> anyhow::Result<HeaderMap> {
+    let signature = signer.sign_hash_sync(&keccak256(group_id))?;
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "authorization",
+        format!("Signature 0x{}", hex::encode(signature.as_bytes())).parse()?,
+    );
+    Ok(headers)
 }
 
 fn group_id_from_nonce_key(nonce_key: U256) -> [u8; 16] {
@@ -579,7 +2447,19 @@ fn build_group_nonce_key(scope_id: u64, group_id: u32) -> U256 {
 }
 
 async fn setup_e2e() -> anyhow::Result<(SocketAddr, RpcState)> {
+    let (api_addr, rpc_state, _state) = setup_e2e_with_state().await?;
+    Ok((api_addr, rpc_state))
+}
+
+async fn setup_e2e_with_state() -> anyhow::Result<(SocketAddr, RpcState, AppState)> {
+    setup_e2e_with_options(true).await
+}
+
+async fn setup_e2e_with_options(
+    use_redis: bool,
+) -> anyhow::Result<(SocketAddr, RpcState, AppState)> {
     dotenvy::dotenv().ok();
+    telemetry::init();
 
     let db_url = env_var("TEST_DATABASE_URL")
         .or_else(|| {
@@ -610,11 +2490,13 @@ async fn setup_e2e() -> anyhow::Result<(SocketAddr, RpcState)> {
     let config = Config {
         server: ServerConfig {
             bind: "127.0.0.1:0".to_string(),
+            shutdown_timeout_ms: 2000,
         },
         database: DatabaseConfig { url: db_url },
-        redis: RedisConfig { url: redis_url },
+        redis: use_redis.then_some(RedisConfig { url: redis_url }),
         rpc: RpcConfig {
             chains: vec![(42431u64, vec![rpc_url])].into_iter().collect(),
+            health: RpcHealthConfig::default(),
         },
         scheduler: SchedulerConfig {
             poll_interval_ms: 100,
@@ -624,6 +2506,11 @@ async fn setup_e2e() -> anyhow::Result<(SocketAddr, RpcState)> {
             retry_max_ms: 500,
             expiry_soon_window_seconds: 3600,
             expiry_soon_retry_max_ms: 5000,
+            escalate_after_attempts: 3,
+            escalate_after_seconds: 60,
+            nonce_lookahead: 0,
+            redis_reconcile_interval_ms: 60_000,
+            rebuild_redis_on_start: false,
         },
         broadcaster: BroadcasterConfig {
             fanout: 1,
@@ -632,42 +2519,69 @@ async fn setup_e2e() -> anyhow::Result<(SocketAddr, RpcState)> {
         watcher: WatcherConfig {
             poll_interval_ms: 1000,
             use_websocket: false,
+            reconcile_interval_ms: 1000,
+            confirmations: 1,
         },
         api: ApiConfig {
             max_body_bytes: 1024 * 1024,
+            require_api_key: false,
+            replacement_bump_percent: 10,
+            legacy_cancel_signatures: false,
+            cancel_auth_max_ttl_seconds: 3600,
         },
+        webhooks: WebhookConfig {
+            poll_interval_ms: 100,
+            allowed_hosts: vec!["127.0.0.1".to_string()],
+            ..WebhookConfig::default()
+        },
+        retention: RetentionConfig::default(),
     };
 
     let db_pool = db::connect(&config.database.url).await?;
     db::migrate(&db_pool).await?;
-    sqlx::query("TRUNCATE txs").execute(&db_pool).await?;
+    sqlx::query(
+        "TRUNCATE txs, txs_archive, tx_call_targets, fee_ladders, broadcast_attempts, webhooks, webhook_deliveries, api_keys",
+    )
+    .execute(&db_pool)
+    .await?;
 
-    let redis_client = redis::Client::open(config.redis.url.as_str())?;
-    let redis_conn = redis::aio::ConnectionManager::new(redis_client).await?;
-    let mut redis_flush = redis_conn.clone();
-    redis::cmd("FLUSHDB")
-        .query_async::<()>(&mut redis_flush)
-        .await?;
+    let redis_conn = match &config.redis {
+        Some(redis) => {
+            let redis_client = redis::Client::open(redis.url.as_str())?;
+            let redis_conn = redis::aio::ConnectionManager::new(redis_client).await?;
+            let mut redis_flush = redis_conn.clone();
+            redis::cmd("FLUSHDB")
+                .query_async::<()>(&mut redis_flush)
+                .await?;
+            Some(redis_conn)
+        }
+        None => None,
+    };
 
     let rpcs = Arc::new(RpcManager::new(&config).await?);
     let state = AppState {
-        config: Arc::new(config),
+        config: ConfigHandle::new(config),
         db: db_pool,
         redis: redis_conn,
         rpcs,
+        events: EventBus::new(),
+        wakeups: Wakeups::new(),
+        shutdown: Shutdown::new(),
         started_at: Utc::now(),
     };
 
     scheduler::start(state.clone());
+    webhooks::start(state.clone());
+    events::start(state.clone());
 
-    let app = api::router(state);
+    let app = api::router(state.clone());
     let listener = TcpListener::bind("127.0.0.1:0").await?;
     let api_addr = listener.local_addr()?;
     tokio::spawn(async move {
         axum::serve(listener, app).await.expect("api server failed");
     });
 
-    Ok((api_addr, rpc_state))
+    Ok((api_addr, rpc_state, state))
 }
 
 async fn acquire_e2e_lock() -> tokio::sync::MutexGuard<'static, ()> {